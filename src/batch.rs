@@ -0,0 +1,249 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::board::*;
+use crate::solver::*;
+
+// Summary of solving a batch of puzzles
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BatchReport {
+    pub total: usize,
+    pub solved: usize,
+    pub unsolvable: usize,
+    pub parse_failed: usize,
+    pub timed_out: usize,
+    // Boards the solver gave up on for any other reason, see `SolveError`
+    pub failed: usize,
+    total_moves: usize,
+}
+
+impl BatchReport {
+    pub fn new() -> BatchReport {
+        Default::default()
+    }
+
+    // Solve `b` and account for the outcome, returning the moves if a solution was found
+    pub fn record(&mut self, b: &Board) -> Option<Vec<Direction>> {
+        let res = Astar::solve(b);
+        self.record_result(&res);
+        res.ok()
+    }
+
+    // Account for the outcome of solving a board elsewhere, e.g. with `solve_batch`
+    pub fn record_result(&mut self, res: &Result<Vec<Direction>, SolveError>) {
+        self.total += 1;
+        match res {
+            Ok(moves) => {
+                self.solved += 1;
+                self.total_moves += moves.len();
+            }
+            Err(SolveError::Unsolvable) => self.unsolvable += 1,
+            Err(SolveError::Timeout) => self.timed_out += 1,
+            Err(_) => self.failed += 1,
+        }
+    }
+
+    pub fn record_parse_failure(&mut self) {
+        self.total += 1;
+        self.parse_failed += 1;
+    }
+
+    // Average number of moves of the solved puzzles
+    pub fn average_length(&self) -> f64 {
+        if self.solved == 0 {
+            return 0.0;
+        }
+        self.total_moves as f64 / self.solved as f64
+    }
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Total: {}", self.total)?;
+        writeln!(f, "Solved: {}", self.solved)?;
+        writeln!(f, "Unsolvable: {}", self.unsolvable)?;
+        writeln!(f, "Parse failed: {}", self.parse_failed)?;
+        writeln!(f, "Timed out: {}", self.timed_out)?;
+        writeln!(f, "Failed: {}", self.failed)?;
+        write!(f, "Average solution length: {:.2}", self.average_length())
+    }
+}
+
+// Split the text of a batch file into boards, which are separated by lines that are blank once
+// trimmed. Works with both LF and CRLF line endings.
+pub fn split_boards(input: &str) -> Vec<String> {
+    let mut boards = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in input.lines().map(str::trim) {
+        if !line.is_empty() {
+            current.push(line);
+        } else if !current.is_empty() {
+            boards.push(current.join("\n"));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        boards.push(current.join("\n"));
+    }
+    boards
+}
+
+// Solve every successfully parsed board in `boards` and summarize the results
+pub fn batch_report<I, E>(boards: I) -> BatchReport
+where
+    I: IntoIterator<Item = Result<Board, E>>,
+{
+    let mut report = BatchReport::new();
+    for res in boards {
+        match res {
+            Ok(b) => {
+                report.record(&b);
+            }
+            Err(_) => report.record_parse_failure(),
+        }
+    }
+    report
+}
+
+// Outcome of a board of a batch file: the result of solving it, or why it could not be parsed
+pub type BatchOutcome = Result<Result<Vec<Direction>, SolveError>, String>;
+
+// Parse and solve every board of a batch file (see `split_boards`), giving up on each one after
+// `timeout` if there is one. Returns the outcome of each board, or why it could not be parsed,
+// along with the summary.
+pub fn solve_batch_input(
+    input: &str,
+    timeout: Option<Duration>,
+) -> (Vec<BatchOutcome>, BatchReport) {
+    let mut report = BatchReport::new();
+    let outcomes = split_boards(input)
+        .iter()
+        .map(|chunk| match chunk.parse::<Board>() {
+            Ok(board) => {
+                let res = match timeout {
+                    Some(timeout) => Astar::run_with_timeout(&board, timeout),
+                    None => Astar::solve(&board),
+                };
+                report.record_result(&res);
+                Ok(res)
+            }
+            Err(msg) => {
+                report.record_parse_failure();
+                Err(msg)
+            }
+        })
+        .collect();
+    (outcomes, report)
+}
+
+// Parse a per-board time limit given in seconds, rejecting negative, infinite and overly large
+// values that do not fit in a `Duration`
+pub fn parse_timeout(secs: &str) -> Result<Duration, String> {
+    let value = secs
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("not a number: {}", secs))?;
+    Duration::try_from_secs_f64(value).map_err(|_| format!("out of range: {}", secs))
+}
+
+// Solve every board in `boards`, giving up on each one after `per_timeout` so that a single
+// hard puzzle cannot stall the whole batch
+pub fn solve_batch(
+    boards: &[Board],
+    per_timeout: Duration,
+) -> Vec<Result<Vec<Direction>, SolveError>> {
+    boards
+        .iter()
+        .map(|b| Astar::run_with_timeout(b, per_timeout))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASY_CONFIG: &str = "1 2 3 4\n5 0 6 8\n9 10 7 11\n13 14 15 12";
+    const UNSOLVABLE_CONFIG: &str = "0 1 2 3\n4 5 6 7\n8 9 10 11\n12 13 14 15";
+    const MALFORMED_CONFIG: &str = "0 1 2 3\n4 5 6 7\n8 9 10 11\n12 13 14";
+
+    #[test]
+    fn mixed_batch() {
+        let boards = vec![EASY_CONFIG, UNSOLVABLE_CONFIG, MALFORMED_CONFIG];
+        let report = batch_report(boards.into_iter().map(Board::from_str_tiles));
+        assert_eq!(report.total, 3);
+        assert_eq!(report.solved, 1);
+        assert_eq!(report.unsolvable, 1);
+        assert_eq!(report.parse_failed, 1);
+        assert_eq!(report.average_length(), 4.0);
+    }
+
+    #[test]
+    fn batch_input() {
+        let input = format!(
+            "{}\n\n{}\n\n{}",
+            EASY_CONFIG, UNSOLVABLE_CONFIG, MALFORMED_CONFIG
+        );
+        let (outcomes, report) = solve_batch_input(&input, None);
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0].as_ref().map(|res| res.is_ok()), Ok(true));
+        assert_eq!(outcomes[1], Ok(Err(SolveError::Unsolvable)));
+        assert!(outcomes[2].is_err());
+        assert_eq!(
+            report,
+            batch_report(split_boards(&input).iter().map(|c| c.parse()))
+        );
+
+        let mut report = BatchReport::new();
+        report.record_result(&Err(SolveError::Exhausted));
+        report.record_result(&Err(SolveError::Cancelled));
+        assert_eq!((report.total, report.failed), (2, 2));
+        assert!(report.to_string().contains("Failed: 2"));
+    }
+
+    #[test]
+    fn split_batch_input() {
+        let input = format!(
+            "\n{}\n\n{}\r\n  \t\r\n\r\n{}\n",
+            EASY_CONFIG,
+            UNSOLVABLE_CONFIG.replace('\n', "\r\n"),
+            MALFORMED_CONFIG
+        );
+        let boards = split_boards(&input);
+        assert_eq!(
+            boards,
+            vec![EASY_CONFIG, UNSOLVABLE_CONFIG, MALFORMED_CONFIG]
+        );
+        assert!(split_boards(" \r\n\n").is_empty());
+    }
+
+    #[test]
+    fn batch_parse_timeout() {
+        assert_eq!(parse_timeout("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_timeout("0"), Ok(Duration::from_secs(0)));
+        for bad in &["inf", "1e300", "-1", "NaN", "soon"] {
+            assert!(parse_timeout(bad).is_err(), "accepted {}", bad);
+        }
+    }
+
+    #[test]
+    fn batch_with_timeout() {
+        let easy = Board::from_str_tiles(EASY_CONFIG).expect("failed to parse easy board");
+        let unsolvable = Board::from_str_tiles(UNSOLVABLE_CONFIG).expect("failed to parse board");
+        // Far too many moves away for a plain Manhattan search to finish in time
+        let hard = Board::new_seeded(1);
+        let results = solve_batch(&[easy, hard, unsolvable], Duration::from_millis(20));
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().map(|moves| moves.len()), Ok(4));
+        assert_eq!(results[1], Err(SolveError::Timeout));
+        assert_eq!(results[2], Err(SolveError::Unsolvable));
+
+        let mut report = BatchReport::new();
+        for res in results.iter() {
+            report.record_result(res);
+        }
+        assert_eq!(
+            (report.solved, report.timed_out, report.unsolvable),
+            (1, 1, 1)
+        );
+    }
+}
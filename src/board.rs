@@ -1,9 +1,15 @@
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::collections::HashSet;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, ErrorKind};
+use std::str::FromStr;
 
-const SIZE: usize = 4;
+use crate::solver::{Astar, SolveError};
+
+pub(crate) const SIZE: usize = 4;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Direction {
@@ -14,6 +20,26 @@ pub enum Direction {
 }
 
 impl Direction {
+    // Every direction, in the order the solvers try them
+    pub fn all() -> [Direction; 4] {
+        [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ]
+    }
+
+    // Bit of the direction in `Board::legal_mask`, following the order of `all()`
+    pub fn bit(self) -> u8 {
+        match self {
+            Direction::Left => 0b0001,
+            Direction::Right => 0b0010,
+            Direction::Up => 0b0100,
+            Direction::Down => 0b1000,
+        }
+    }
+
     pub fn opposite(self) -> Direction {
         match self {
             Direction::Left => Direction::Right,
@@ -27,12 +53,81 @@ impl Direction {
         other.opposite() == self
     }
 
+    // Direction after rotating the board clockwise by `rotation`
+    pub fn rotated(self, rotation: Rotation) -> Direction {
+        let quarter = |d: Direction| match d {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        };
+        match rotation {
+            Rotation::Deg90 => quarter(self),
+            Rotation::Deg180 => self.opposite(),
+            Rotation::Deg270 => quarter(self).opposite(),
+        }
+    }
+
+    // Direction after reflecting the board across its main diagonal, see `Board::transposed`
+    pub fn transposed(self) -> Direction {
+        match self {
+            Direction::Left => Direction::Up,
+            Direction::Right => Direction::Down,
+            Direction::Up => Direction::Left,
+            Direction::Down => Direction::Right,
+        }
+    }
+
+    pub fn horizontal(self) -> bool {
+        self == Direction::Left || self == Direction::Right
+    }
+
     pub fn value(self) -> isize {
+        self.offset(SIZE)
+    }
+
+    // Change in the index of the empty tile on a board `width` tiles wide
+    pub fn offset(self, width: usize) -> isize {
         match self {
             Direction::Left => -1,
             Direction::Right => 1,
-            Direction::Up => -(SIZE as isize),
-            Direction::Down => SIZE as isize,
+            Direction::Up => -(width as isize),
+            Direction::Down => width as isize,
+        }
+    }
+}
+
+// Clockwise rotation by a multiple of 90 degrees
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Rotation {
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+// Friendly difficulty rating of a board, see `Board::difficulty_label`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+// Smallest estimated number of moves for each difficulty above `Difficulty::Easy`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DifficultyThresholds {
+    pub medium: usize,
+    pub hard: usize,
+    pub expert: usize,
+}
+
+impl Default for DifficultyThresholds {
+    fn default() -> DifficultyThresholds {
+        DifficultyThresholds {
+            medium: 10,
+            hard: 20,
+            expert: 35,
         }
     }
 }
@@ -43,52 +138,140 @@ impl fmt::Display for Direction {
     }
 }
 
-#[derive(Debug, Default, Clone, Eq, PartialEq)]
-pub struct Board {
-    tiles: [u8; SIZE * SIZE],
+impl FromStr for Direction {
+    type Err = String;
+
+    // Accepts the `Display` names as well as their initials, ignoring case
+    fn from_str(s: &str) -> Result<Direction, String> {
+        match s.to_lowercase().as_str() {
+            "left" | "l" => Ok(Direction::Left),
+            "right" | "r" => Ok(Direction::Right),
+            "up" | "u" => Ok(Direction::Up),
+            "down" | "d" => Ok(Direction::Down),
+            _ => Err(format!("invalid direction: {}", s)),
+        }
+    }
+}
+
+// A `N` x `N` sliding puzzle, by default the 15-puzzle. Boards are ordered lexicographically by
+// their tiles (`empty` follows from the tiles).
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Board<const N: usize = SIZE> {
+    tiles: Vec<u8>,
     empty: usize,
 }
 
-impl Board {
-    pub fn new() -> Board {
-        let mut b: Board = Default::default();
-        for i in 0..16u8 {
-            b.tiles[i as usize] = i;
+impl<const N: usize> Default for Board<N> {
+    fn default() -> Board<N> {
+        // Every board is built from this one, so no board can be made for a size whose tiles do
+        // not fit in a `u8` or that has no room to move
+        const { assert!(N >= 2 && N * N <= 256, "unsupported board size") };
+        Board {
+            tiles: vec![0; N * N],
+            empty: 0,
+        }
+    }
+}
+
+// Consistent with `Eq` since `empty` follows from the tiles
+impl<const N: usize> Hash for Board<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tiles.hash(state);
+    }
+}
+
+// Everything that works the same whatever the size of the board. The other features assume
+// the 15-puzzle.
+impl<const N: usize> Board<N> {
+    pub fn new() -> Board<N> {
+        let mut b: Board<N> = Default::default();
+        for (i, t) in b.tiles.iter_mut().enumerate() {
+            *t = i as u8;
         }
         b
     }
 
-    pub fn new_from(tiles: &[u8]) -> Result<Board, &'static str> {
-        let mut b = Board {
-            tiles: [0; 16],
-            empty: 0,
-        };
+    // Build a board from its tiles in row-major order. The size of the board is not inferred
+    // from `tiles`, it comes from the type, e.g. `Board::<3>::new_from` for the 8-puzzle.
+    pub fn new_from(tiles: &[u8]) -> Result<Board<N>, &'static str> {
+        let mut b: Board<N> = Default::default();
+        if tiles.len() > b.tiles.len() {
+            return Err("too many tiles");
+        }
         let mut nums = HashSet::new();
         for (i, &t) in tiles.iter().enumerate() {
             nums.insert(t);
-            match t {
+            match t as usize {
                 0 => b.empty = i,
-                1..=15 => b.tiles[i] = t,
-                _ => return Err("tiles should be in the range [0, 15]"),
+                t if t < N * N => b.tiles[i] = t as u8,
+                _ => return Err("tiles should be less than the number of cells"),
             }
         }
         match nums.len() {
-            0..=15 => Err("missing or repeated tiles"),
-            16 => Ok(b),
+            n if n < N * N => Err("missing or repeated tiles"),
+            n if n == N * N => Ok(b),
             _ => Err("too many tiles"),
         }
     }
 
-    pub fn new_random() -> Board {
-        let mut b = Board::new();
-        b.shuffle();
+    // Same as `new_from` for a `size` x `size` board (which must be `N`), but validating the tiles as they are
+    // consumed so bad input is rejected without reading the rest of `iter`
+    pub fn new_from_iter<I>(iter: I, size: usize) -> Result<Board<N>, &'static str>
+    where
+        I: Iterator<Item = u8>,
+    {
+        if size != N {
+            return Err("size does not match the board type");
+        }
+        let mut b: Board<N> = Default::default();
+        let mut seen = vec![false; N * N];
+        let mut count = 0;
+        for t in iter {
+            if count == b.tiles.len() {
+                return Err("too many tiles");
+            }
+            if t as usize >= b.tiles.len() {
+                return Err("tiles should be less than the number of cells");
+            }
+            if seen[t as usize] {
+                return Err("missing or repeated tiles");
+            }
+            seen[t as usize] = true;
+            if t == 0 {
+                b.empty = count;
+            }
+            b.tiles[count] = t;
+            count += 1;
+        }
+        if count < b.tiles.len() {
+            return Err("missing or repeated tiles");
+        }
+        Ok(b)
+    }
+
+    // The board `solved()` compares against: tiles in order, with the empty tile last
+    pub fn goal() -> Board<N> {
+        let mut b: Board<N> = Default::default();
+        for (i, t) in b.tiles.iter_mut().take(N * N - 1).enumerate() {
+            *t = i as u8 + 1;
+        }
+        b.empty = N * N - 1;
         b
     }
 
-    pub fn tiles(&self) -> &[u8; 16] {
+    // Optimal solution of the board with the default solver, see `Astar::solve`
+    pub fn solve(&self) -> Result<Vec<Direction>, SolveError> {
+        Astar::solve(self)
+    }
+
+    pub fn tiles(&self) -> &[u8] {
         &self.tiles
     }
 
+    pub fn empty_index(&self) -> usize {
+        self.empty
+    }
+
     fn safe_pos(&self, step: isize) -> usize {
         let empty = self.empty;
         if step.is_negative() {
@@ -104,258 +287,2028 @@ impl Board {
         }
     }
 
-    pub fn slide_safe(&mut self, dir: Direction) -> Result<bool, &'static str> {
-        let pos = self.safe_pos(dir.value());
-        if pos == self.empty || pos >= self.tiles.len() {
-            return Err("Invalid move");
-        }
-        if (dir == Direction::Left || dir == Direction::Right)
-            && ((pos / SIZE) != (self.empty / SIZE))
-        {
+    // Same as `slide` but checking the move first. Returns the tile that took the place of the
+    // empty tile.
+    pub fn slide_safe(&mut self, dir: Direction) -> Result<u8, &'static str> {
+        if !self.can_slide(dir) {
             return Err("Invalid move");
         }
+        let pos = self.safe_pos(dir.offset(N));
         self.tiles.swap(self.empty, pos);
+        let moved = self.tiles[self.empty];
         self.empty = pos;
-        Ok(true)
+        debug_assert_eq!(self.tiles[self.empty], 0, "empty index out of sync");
+        Ok(moved)
+    }
+
+    // Same as `slide_safe` for callers that only care whether the move was made
+    pub fn try_slide(&mut self, dir: Direction) -> Option<Direction> {
+        self.slide_safe(dir).ok().map(|_| dir)
     }
 
     pub fn slide(&mut self, dir: Direction) {
-        let pos = self.safe_pos(dir.value());
+        let pos = self.safe_pos(dir.offset(N));
         self.tiles.swap(self.empty, pos);
         self.empty = pos;
+        debug_assert_eq!(self.tiles[self.empty], 0, "empty index out of sync");
     }
 
     pub fn can_slide(&self, dir: Direction) -> bool {
-        let pos = self.safe_pos(dir.value());
+        let pos = self.safe_pos(dir.offset(N));
         pos != self.empty
             && pos < self.tiles.len()
-            && (dir == Direction::Up
-                || dir == Direction::Down
-                || (pos / SIZE) == (self.empty / SIZE))
+            && (dir == Direction::Up || dir == Direction::Down || (pos / N) == (self.empty / N))
     }
 
-    pub fn shuffle(&mut self) {
-        self.tiles.shuffle(&mut thread_rng());
-        // Since we know the board is valid, it must contain the empty tile (0)
-        self.empty = self.tiles.iter().position(|&x| x == 0).expect("no empty tile?!");
+    // Positions next to `pos`, i.e. the ones the empty tile at `pos` can move to
+    pub(crate) fn neighbours(pos: usize) -> Vec<usize> {
+        let (row, col) = (pos / N, pos % N);
+        let mut next = Vec::with_capacity(4);
+        if row > 0 {
+            next.push(pos - N);
+        }
+        if row < N - 1 {
+            next.push(pos + N);
+        }
+        if col > 0 {
+            next.push(pos - 1);
+        }
+        if col < N - 1 {
+            next.push(pos + 1);
+        }
+        next
     }
 
     pub fn solved(&self) -> bool {
-        self.empty == self.tiles.len() - 1 && self.tiles.last() == Some(&0u8) &&
-            self.tiles.windows(2).all(|win| win[1] == 0 || win[0] < win[1])
+        self.empty == N * N - 1 && (0..N * N).all(|i| self.in_place(i))
     }
 
-    pub fn solvable(&self) -> bool {
-        let invs: usize = self
-            .tiles
+    // Whether the tiles are laid out as in `goal`, for puzzles with a custom goal
+    pub fn solved_to(&self, goal: &Board<N>) -> bool {
+        self.empty == goal.empty && self.tiles == goal.tiles
+    }
+
+    // Whether the tile at `idx` is the one the solved board has there
+    fn in_place(&self, idx: usize) -> bool {
+        self.tiles[idx] as usize == (idx + 1) % (N * N)
+    }
+
+    // Number of pairs of tiles, the empty one aside, that appear in the wrong order when reading
+    // the board row by row
+    pub fn inversions(&self) -> usize {
+        self.tiles
             .iter()
             .enumerate()
-            .map(|(i, &t)| self.tiles.iter().skip(i).filter(|&&x| x != 0 && x < t).count())
-            .sum();
-        // (empty tile in even row, even # of inversions)
-        match ((self.empty / SIZE) % 2 == 0, invs % 2 == 0) {
-            (true, false) | (false, true) => true,
-            _ => false,
-        }
+            .map(|(i, &t)| {
+                self.tiles
+                    .iter()
+                    .skip(i)
+                    .filter(|&&x| x != 0 && x < t)
+                    .count()
+            })
+            .sum()
     }
-}
 
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for i in 0..4 {
-            write!(f, "[")?;
-            for j in 0..4 {
-                write!(f, "{}", self.tiles[i * 4 + j])?;
-                if j != 3 {
-                    write!(f, " ")?;
-                }
-            }
-            write!(f, "]")?;
-            if i != 3 {
-                writeln!(f)?;
-            }
+    // Reachability invariant: inversion parity, XOR parity of the empty tile's row on boards of
+    // even width. Two boards can be turned into one another iff they share the same parity class.
+    pub fn parity_class(&self) -> bool {
+        let odd = self.inversions() % 2 != 0;
+        if N % 2 == 0 {
+            odd ^ ((self.empty / N) % 2 != 0)
+        } else {
+            odd
         }
-        Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::collections::HashSet;
-
-    const DEFAULT_CONFIG: &'static [u8; SIZE * SIZE] =
-        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
-    const SOLVED_CONFIG: &'static [u8; SIZE * SIZE] =
-        &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
-    const SOLVABLE_CONFIG: &'static [u8; SIZE * SIZE] =
-        &[1, 2, 3, 4, 0, 5, 6, 7, 8, 10, 11, 9, 12, 13, 14, 15];
 
-    #[test]
-    fn dir_is_opposite() {
-        assert_eq!(Direction::Up, Direction::Down.opposite());
-        assert_eq!(Direction::Down, Direction::Up.opposite());
-        assert_eq!(Direction::Left, Direction::Right.opposite());
-        assert_eq!(Direction::Right, Direction::Left.opposite());
+    // Number of characters taken by a tile when printing the board: the digits of the largest
+    // tile and a space to separate it from the previous one
+    pub(crate) fn cell_width() -> usize {
+        (N * N - 1).to_string().len() + 1
+    }
 
-        assert_ne!(Direction::Up, Direction::Up.opposite());
-        assert_ne!(Direction::Up, Direction::Left.opposite());
-        assert_ne!(Direction::Up, Direction::Right.opposite());
+    pub fn solvable(&self) -> bool {
+        // The solved board has no inversions and, on boards of even width, the empty tile in an
+        // odd row
+        self.parity_class() == (N % 2 == 0)
     }
+}
 
-    #[test]
-    fn dir_opposes() {
-        assert!(Direction::Up.opposites(Direction::Down));
-        assert!(Direction::Down.opposites(Direction::Up));
-        assert!(Direction::Left.opposites(Direction::Right));
-        assert!(Direction::Right.opposites(Direction::Left));
+impl Board {
+    // Tiles in order with the empty tile at `index`, i.e. the solved board with the empty tile
+    // moved to `index` by shifting the tiles in between. Panics if `index` is out of the board.
+    pub fn with_blank_at(index: usize) -> Board {
+        let mut b: Board = Default::default();
+        assert!(index < b.tiles.len(), "index {} out of the board", index);
+        let mut next = 1;
+        for (i, t) in b.tiles.iter_mut().enumerate() {
+            if i != index {
+                *t = next;
+                next += 1;
+            }
+        }
+        b.empty = index;
+        b
+    }
 
-        assert!(!Direction::Up.opposites(Direction::Up));
-        assert!(!Direction::Up.opposites(Direction::Left));
-        assert!(!Direction::Up.opposites(Direction::Right));
+    // Build a board from any source of tile values in row-major order
+    pub fn from_shuffled<I: IntoIterator<Item = u8>>(iter: I) -> Result<Board, String> {
+        let tiles: Vec<u8> = iter.into_iter().collect();
+        if tiles.len() != SIZE * SIZE {
+            return Err(format!(
+                "expected {} numbers, got {}",
+                SIZE * SIZE,
+                tiles.len()
+            ));
+        }
+        Board::new_from(&tiles).map_err(String::from)
     }
 
-    #[test]
-    fn dir_value() {
-        assert_eq!(Direction::Up.value(), -4);
-        assert_eq!(Direction::Down.value(), 4);
-        assert_eq!(Direction::Left.value(), -1);
-        assert_eq!(Direction::Right.value(), 1);
+    // Same as `s.parse::<Board>()`
+    pub fn from_str_tiles(s: &str) -> Result<Board, String> {
+        s.parse()
     }
 
-    fn is_board_valid(b: &Board) -> bool {
-        let mut tiles = HashSet::new();
-        for tile in &b.tiles {
-            tiles.insert(tile);
+    // Build a board from the tiles given in `spec`, filling the `None` cells with the unused
+    // tiles in ascending order (the empty tile first)
+    pub fn from_partial(spec: &[Option<u8>]) -> Result<Board, String> {
+        if spec.len() != SIZE * SIZE {
+            return Err(format!(
+                "expected {} cells, got {}",
+                SIZE * SIZE,
+                spec.len()
+            ));
         }
-        if tiles.len() != b.tiles.len() {
-            return false;
+        let mut used = [false; SIZE * SIZE];
+        for &t in spec.iter().flatten() {
+            match used.get_mut(t as usize) {
+                Some(true) => return Err(format!("tile {} is given more than once", t)),
+                Some(seen) => *seen = true,
+                None => return Err(format!("invalid tile: {}", t)),
+            }
         }
-        for tile in &tiles {
-            let value = **tile as usize;
-            if value >= b.tiles.len() {
-                return false;
+        let mut unused = (0..(SIZE * SIZE) as u8).filter(|&t| !used[t as usize]);
+        let tiles: Vec<u8> = spec
+            .iter()
+            .map(|t| {
+                t.or_else(|| unused.next())
+                    .expect("as many unused tiles as free cells")
+            })
+            .collect();
+        Board::new_from(&tiles).map_err(String::from)
+    }
+
+    // Read the first board of `r`, consuming the input only up to the whitespace that ends its
+    // last number so whatever follows (e.g. another board) is left for the next reader. Plain
+    // readers can be wrapped in a `BufReader`.
+    pub fn from_reader<R: BufRead>(mut r: R) -> Result<Board, String> {
+        let mut bytes = Vec::new();
+        let mut numbers = 0;
+        let mut in_number = false;
+        while numbers < SIZE * SIZE {
+            let buf = match r.fill_buf() {
+                Ok([]) => break,
+                Ok(buf) => buf,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(format!("IO error: {}", err)),
+            };
+            let mut used = 0;
+            for &byte in buf {
+                used += 1;
+                bytes.push(byte);
+                if !byte.is_ascii_whitespace() {
+                    in_number = true;
+                } else if in_number {
+                    in_number = false;
+                    numbers += 1;
+                    if numbers == SIZE * SIZE {
+                        break;
+                    }
+                }
             }
+            r.consume(used);
         }
-        true
+        let text = String::from_utf8(bytes).map_err(|_| "input is not valid UTF-8".to_string())?;
+        Board::from_str_tiles(&text)
     }
 
-    #[test]
-    fn create_board() {
-        let b = Board::new();
-        assert_eq!(b.tiles, *DEFAULT_CONFIG);
-        assert_eq!(b.empty, 0);
+    // Uniformly random solvable board. Use `shuffle` for boards that may not be solvable.
+    pub fn new_random() -> Board {
+        Board::random_solvable(&mut thread_rng())
+    }
 
-        let b = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
-        assert_eq!(b.tiles, *DEFAULT_CONFIG);
-        assert_eq!(b.empty, 0);
+    // Solvable random board that only depends on `seed`
+    pub fn new_seeded(seed: u64) -> Board {
+        Board::random_solvable(&mut StdRng::seed_from_u64(seed))
+    }
 
-        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
-        assert_eq!(b.tiles, *SOLVED_CONFIG);
-        assert_eq!(b.empty, 15);
+    // Endless sequence of distinct solvable random boards that only depends on `seed`
+    pub fn solvable_stream(seed: u64) -> impl Iterator<Item = Board> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut seen = HashSet::new();
+        std::iter::repeat_with(move || Board::random_solvable(&mut rng))
+            .filter(move |b| seen.insert(b.to_u64()))
     }
 
-    #[test]
-    fn board_move() {
+    fn random_solvable<R: Rng + ?Sized>(rng: &mut R) -> Board {
         let mut b = Board::new();
-
-        // Move all the way to the right
-        for i in 0..3 {
-            assert!(b.can_slide(Direction::Right));
-            b.slide(Direction::Right);
-            assert!(is_board_valid(&b));
-            assert_eq!(b.empty, i + 1);
-        }
-        // Try to go over the right edge
-        assert!(!b.can_slide(Direction::Right));
-        let res = b.slide_safe(Direction::Right);
-        assert!(res.is_err());
-        assert!(is_board_valid(&b));
-        assert_eq!(b.empty, 3);
-
-        // Move all the way down
-        for i in 0..3 {
-            assert!(b.can_slide(Direction::Down));
-            b.slide(Direction::Down);
-            assert!(is_board_valid(&b));
-            assert_eq!(b.empty, (i + 1) * SIZE + 3);
+        b.shuffle_with(rng);
+        if !b.solvable() {
+            // Swapping two non-empty tiles flips the parity
+            let (i, j) = if b.empty < 2 { (2, 3) } else { (0, 1) };
+            b.tiles.swap(i, j);
         }
-        // Try to go over the bottom edge
-        assert!(!b.can_slide(Direction::Down));
-        let res = b.slide_safe(Direction::Down);
-        assert!(res.is_err());
-        assert!(is_board_valid(&b));
-        assert_eq!(b.empty, 15);
+        b
+    }
 
-        // Move all the way to the left
-        for i in 0..3 {
-            assert!(b.can_slide(Direction::Left));
-            b.slide(Direction::Left);
-            assert!(is_board_valid(&b));
-            assert_eq!(b.empty, b.tiles.len() - 1 - i - 1);
-        }
-        // Try to go over the left edge
-        assert!(!b.can_slide(Direction::Left));
-        let res = b.slide_safe(Direction::Left);
-        assert!(res.is_err());
-        assert!(is_board_valid(&b));
-        assert_eq!(b.empty, 12);
+    // Puzzle of the day: the same solvable board for everyone on a given (year, month, day)
+    pub fn daily(date: (u16, u8, u8)) -> Board {
+        let (year, month, day) = date;
+        Board::new_seeded((year as u64) << 16 | (month as u64) << 8 | day as u64)
+    }
 
-        // Move all the way up
-        for i in 0..3 {
-            assert!(b.can_slide(Direction::Up));
-            b.slide(Direction::Up);
-            assert!(is_board_valid(&b));
-            assert_eq!(b.empty, (3 - i - 1) * SIZE);
-        }
-        // Try to go over the top edge
-        assert!(!b.can_slide(Direction::Up));
-        let res = b.slide_safe(Direction::Up);
-        assert!(res.is_err());
-        assert!(is_board_valid(&b));
-        assert_eq!(b.empty, 0);
+    // Boards one legal move away, along with that move, leaving out those for which `skip`
+    // returns true, e.g. the ones already visited by a caller's own search
+    pub fn successors_excluding<F: Fn(&Board) -> bool>(&self, skip: F) -> Vec<(Board, Direction)> {
+        Direction::all()
+            .iter()
+            .filter(|&&dir| self.can_slide(dir))
+            .map(|&dir| {
+                let mut b = self.clone();
+                b.slide(dir);
+                (b, dir)
+            })
+            .filter(|(b, _)| !skip(b))
+            .collect()
     }
 
-    #[test]
-    fn random_board() {
-        let b = Board::new_random();
-        assert!(is_board_valid(&b));
+    // Make the move bound to `key`, see `direction_from_key`
+    pub fn apply_keypress(&mut self, key: char) -> Result<(), &'static str> {
+        let dir = direction_from_key(key).ok_or("Unknown key")?;
+        self.slide_safe(dir).map(|_| ())
+    }
 
-        let mut b = Board::new();
-        assert!(is_board_valid(&b));
-        b.shuffle();
-        assert!(is_board_valid(&b));
+    // Legal moves as a bit mask, see `Direction::bit`
+    pub fn legal_mask(&self) -> u8 {
+        Direction::all()
+            .iter()
+            .filter(|&&dir| self.can_slide(dir))
+            .fold(0, |mask, dir| mask | dir.bit())
     }
 
-    #[test]
-    fn board_solvable() {
-        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
-        assert!(b.solvable());
+    // Index in `tiles()` of the cell at (`row`, `col`)
+    pub fn tile_index_at(row: usize, col: usize) -> usize {
+        assert!(
+            row < SIZE && col < SIZE,
+            "cell ({}, {}) out of the board",
+            row,
+            col
+        );
+        row * SIZE + col
+    }
 
-        let b = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
-        assert!(!b.solvable());
+    // Tiles next to the empty tile, i.e. the ones that can slide into it
+    pub fn movable_tiles(&self) -> Vec<u8> {
+        Direction::all()
+            .iter()
+            .filter(|&&dir| self.can_slide(dir))
+            .map(|&dir| self.tiles[self.safe_pos(dir.value())])
+            .collect()
+    }
 
-        let b = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
-        assert!(b.solvable());
+    // Number of legal moves from the current position, without generating them
+    pub fn move_count(&self) -> usize {
+        let (row, col) = (self.empty / SIZE, self.empty % SIZE);
+        let edges = [row == 0, row == SIZE - 1, col == 0, col == SIZE - 1];
+        4 - edges.iter().filter(|&&e| e).count()
     }
 
-    #[test]
-    fn board_solved() {
-        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
-        assert!(b.solved());
+    // Shortest sequence of moves that turns the solved board into this one, which is the
+    // optimal solution played backwards
+    pub fn minimal_scramble(&self) -> Option<Vec<Direction>> {
+        Astar::run_reverse(self)
+    }
 
-        let b = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
-        assert!(!b.solved());
+    // Random walk from the solved board that stops as soon as the Manhattan heuristic reaches
+    // `target`, a cheap proxy for difficulty. `target` should be well under the largest
+    // Manhattan distance a board can have or the walk may never end.
+    pub fn scramble_to_heuristic<R: Rng + ?Sized>(target: usize, rng: &mut R) -> Board {
+        let mut b = Board::with_blank_at(SIZE * SIZE - 1);
+        let mut last = None;
+        while b.manhattan_cost() < target {
+            let dir = b.random_move(last, rng);
+            b.slide(dir);
+            last = Some(dir);
+        }
+        b
+    }
+
+    // Random board, with the empty tile in the last position, whose `inversions()` is `target`.
+    // Starting from the solved board, it repeatedly swaps two consecutive tiles that are in
+    // order, which adds exactly one inversion. None if `target` exceeds the number of pairs of
+    // tiles. Half of these boards cannot be solved, depending on the parity of `target`.
+    pub fn with_inversions<R: Rng + ?Sized>(target: usize, rng: &mut R) -> Option<Board> {
+        let n = SIZE * SIZE - 1;
+        if target > n * (n - 1) / 2 {
+            return None;
+        }
+        let mut b = Board::with_blank_at(n);
+        for _ in 0..target {
+            let ordered: Vec<usize> = (0..n - 1)
+                .filter(|&i| b.tiles[i] < b.tiles[i + 1])
+                .collect();
+            let &i = ordered
+                .choose(rng)
+                .expect("fewer inversions than pairs of tiles");
+            b.tiles.swap(i, i + 1);
+        }
+        Some(b)
+    }
+
+    // Random solvable board whose optimal solution moves the empty tile in all four directions
+    pub fn require_all_directions<R: Rng + ?Sized>(rng: &mut R) -> Board {
+        loop {
+            let b = Board::scramble_to_heuristic(12, rng);
+            if let Ok(moves) = b.solve() {
+                if Direction::all().iter().all(|dir| moves.contains(dir)) {
+                    return b;
+                }
+            }
+        }
+    }
+
+    // Trajectory of `steps` random legal moves from this board, as (state, action) pairs where
+    // the action is applied to the state. Moves are never immediately undone.
+    pub fn random_walk<R: Rng + ?Sized>(
+        &self,
+        steps: usize,
+        rng: &mut R,
+    ) -> Vec<(Board, Direction)> {
+        let mut walk = Vec::with_capacity(steps);
+        let mut b = self.clone();
+        let mut last = None;
+        for _ in 0..steps {
+            let dir = b.random_move(last, rng);
+            walk.push((b.clone(), dir));
+            b.slide(dir);
+            last = Some(dir);
+        }
+        walk
+    }
+
+    // Random legal move that does not undo `last`
+    fn random_move<R: Rng + ?Sized>(&self, last: Option<Direction>, rng: &mut R) -> Direction {
+        let dirs: Vec<Direction> = Direction::all()
+            .iter()
+            .cloned()
+            .filter(|&d| self.can_slide(d) && last.is_none_or(|l| !l.opposites(d)))
+            .collect();
+        // Every position has at least two legal moves, so one is left after excluding `last`
+        *dirs.choose(rng).expect("no legal moves?!")
+    }
+
+    // Sum of the Manhattan distances of every tile to its goal position
+    pub fn manhattan_cost(&self) -> usize {
+        Astar::manhattan(self)
+    }
+
+    // Manhattan distance of each tile to its goal position, laid out like `tiles()`. The empty
+    // tile counts as 0.
+    pub fn distance_map(&self) -> [usize; SIZE * SIZE] {
+        let mut map = [0; SIZE * SIZE];
+        for (i, &t) in self.tiles.iter().enumerate() {
+            map[i] = Astar::manhattan_dist(t, i, SIZE);
+        }
+        map
+    }
+
+    // Cheap lower bound on the moves left to solve the board, 0 only if it is already solved
+    pub fn moves_from_solved_estimate(&self) -> usize {
+        if self.solved() {
+            0
+        } else {
+            self.manhattan_lower_bound()
+        }
+    }
+
+    // Pure Manhattan lower bound on the number of moves left, for comparison with the tighter
+    // `Astar::linear_conflict`
+    pub fn manhattan_lower_bound(&self) -> usize {
+        Astar::manhattan(self)
+    }
+
+    // Point `empty` back at the empty tile in case it went stale (e.g. after deserializing).
+    // Returns whether a correction was made.
+    pub fn normalize(&mut self) -> bool {
+        match self.tiles.iter().position(|&x| x == 0) {
+            Some(pos) if pos != self.empty => {
+                self.empty = pos;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // How far the tiles are from home in total, to measure how well mixed a board is. This is
+    // the same value as the Manhattan heuristic.
+    pub fn displacement_sum(&self) -> usize {
+        self.manhattan_cost()
+    }
+
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut thread_rng());
+    }
+
+    // Uniformly shuffle the tiles using `rng`; the result may not be solvable
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.tiles.shuffle(rng);
+        // Since we know the board is valid, it must contain the empty tile (0)
+        self.empty = self
+            .tiles
+            .iter()
+            .position(|&x| x == 0)
+            .expect("no empty tile?!");
+    }
+
+    // Same as `shuffle_with`, returning the permutation applied: the tile now at index `i` was
+    // at index `perm[i]` before
+    pub fn shuffle_tracked<R: Rng + ?Sized>(&mut self, rng: &mut R) -> [usize; SIZE * SIZE] {
+        let mut perm = [0; SIZE * SIZE];
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = i;
+        }
+        perm.shuffle(rng);
+        let old = self.tiles.clone();
+        for (i, &p) in perm.iter().enumerate() {
+            self.tiles[i] = old[p];
+        }
+        self.empty = perm
+            .iter()
+            .position(|&p| p == self.empty)
+            .expect("no empty tile?!");
+        perm
+    }
+
+    // 32-bit FNV-1a hash of the tiles. Equal boards always have the same checksum and the value
+    // is stable across runs, but different boards may collide.
+    pub fn checksum(&self) -> u32 {
+        self.tiles.iter().fold(0x811c_9dc5, |hash, &t| {
+            (hash ^ t as u32).wrapping_mul(0x0100_0193)
+        })
+    }
+
+    // Values of the tiles already sitting in their goal position (the empty tile excluded)
+    pub fn correct_tiles(&self) -> Vec<u8> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|&(i, &t)| t != 0 && t as usize == i + 1)
+            .map(|(_, &t)| t)
+            .collect()
+    }
+
+    // Whether `tile` can be brought to position `target` by sliding tiles, without ever moving
+    // the tiles at the `frozen` positions. Only the empty tile and `tile` matter, so this is a
+    // breadth-first search over their positions.
+    pub fn can_place_without_disturbing(&self, tile: u8, target: usize, frozen: &[usize]) -> bool {
+        let start = match self.tiles.iter().position(|&t| t == tile) {
+            Some(pos) if tile != 0 => pos,
+            _ => return false,
+        };
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert((self.empty, start));
+        queue.push_back((self.empty, start));
+        while let Some((empty, pos)) = queue.pop_front() {
+            if pos == target {
+                return true;
+            }
+            for n in Board::<SIZE>::neighbours(empty) {
+                if frozen.contains(&n) {
+                    continue;
+                }
+                // Sliding the tile at `n` into the empty cell; it may be the one we place
+                let state = (n, if n == pos { empty } else { pos });
+                if seen.insert(state) {
+                    queue.push_back(state);
+                }
+            }
+        }
+        false
+    }
+
+    // Mirror image of the board across its main diagonal, with the tiles renumbered so the
+    // solved board maps to itself. This is the only symmetry of the square that keeps the empty
+    // tile's goal in place, so solutions of the mirror are the transposed solutions of `self`.
+    pub fn transposed(&self) -> Board {
+        let flip = |i: usize| (i % SIZE) * SIZE + i / SIZE;
+        let mut b = self.clone();
+        for (i, &t) in self.tiles.iter().enumerate() {
+            b.tiles[flip(i)] = if t == 0 {
+                0
+            } else {
+                flip(t as usize - 1) as u8 + 1
+            };
+        }
+        b.empty = flip(self.empty);
+        b
+    }
+
+    // Smallest of the board and its mirror image; symmetric boards share the same canonical form.
+    // Of the 8 symmetries of the square only the identity and `transposed` are used on purpose:
+    // the rotations and the other reflections move the empty tile's goal to another corner, and
+    // renumbering the tiles cannot bring it back. A rotated board is therefore a different
+    // puzzle, with its own optimal solution length, and must not share a canonical form.
+    pub fn canonical(&self) -> Board {
+        self.clone().min(self.transposed())
+    }
+
+    // Whether `other` is the same puzzle as `self` up to symmetry, i.e. has the same canonical
+    // form. That means `self` or its mirror image across the main diagonal: rotations are left
+    // out intentionally, see `canonical`.
+    pub fn symmetry_eq(&self, other: &Board) -> bool {
+        self.canonical() == other.canonical()
+    }
+
+    // Board turned clockwise by `rotation`, each tile moving along with its cell. Unlike
+    // `transposed` the tiles keep their numbers, so the result is a different puzzle.
+    pub fn rotated(&self, rotation: Rotation) -> Board {
+        let quarters = match rotation {
+            Rotation::Deg90 => 1,
+            Rotation::Deg180 => 2,
+            Rotation::Deg270 => 3,
+        };
+        let turn = |i: usize| (i % SIZE) * SIZE + SIZE - 1 - i / SIZE;
+        (0..quarters).fold(self.clone(), |b, _| b.moved_cells(turn))
+    }
+
+    // Whether `other` has the same tiles as `self` once the board is turned and/or flipped over,
+    // e.g. `self.rotated(Rotation::Deg90)`. Tiles are compared by number, so unlike with
+    // `symmetry_eq` the two boards are usually different puzzles.
+    pub fn rotation_eq(&self, other: &Board) -> bool {
+        let flip = |i: usize| (i % SIZE) * SIZE + i / SIZE;
+        let rotations = [Rotation::Deg90, Rotation::Deg180, Rotation::Deg270];
+        [self.clone(), self.moved_cells(flip)].iter().any(|b| {
+            b == other
+                || rotations
+                    .iter()
+                    .any(|&rotation| b.rotated(rotation) == *other)
+        })
+    }
+
+    // Board with the tile of every cell `i` moved to cell `to(i)`
+    fn moved_cells<F: Fn(usize) -> usize>(&self, to: F) -> Board {
+        let mut b = self.clone();
+        for (i, &t) in self.tiles.iter().enumerate() {
+            b.tiles[to(i)] = t;
+        }
+        b.empty = to(self.empty);
+        b
+    }
+
+    // Index in the optimal solution of the move made from the most constrained board, i.e. the
+    // one whose Manhattan heuristic is the highest relative to the number of moves left. None if
+    // the board is solved or cannot be solved.
+    pub fn bottleneck_move(&self) -> Option<usize> {
+        let moves = self.solve().ok()?;
+        let mut b = self.clone();
+        let mut best: Option<(usize, usize, usize)> = None;
+        for (i, &dir) in moves.iter().enumerate() {
+            let (h, left) = (b.manhattan_cost(), moves.len() - i);
+            // h / left > best_h / best_left
+            if best.is_none_or(|(_, best_h, best_left)| h * best_left > best_h * left) {
+                best = Some((i, h, left));
+            }
+            b.slide(dir);
+        }
+        best.map(|(i, _, _)| i)
+    }
+
+    // Rate the board with the default thresholds, see `difficulty_label_with`
+    pub fn difficulty_label(&self) -> Difficulty {
+        self.difficulty_label_with(&DifficultyThresholds::default())
+    }
+
+    // Rate the board by its linear conflict estimate of the moves left, which is cheap to
+    // compute even for boards too hard to solve quickly
+    pub fn difficulty_label_with(&self, thresholds: &DifficultyThresholds) -> Difficulty {
+        let estimate = Astar::linear_conflict(self);
+        if estimate >= thresholds.expert {
+            Difficulty::Expert
+        } else if estimate >= thresholds.hard {
+            Difficulty::Hard
+        } else if estimate >= thresholds.medium {
+            Difficulty::Medium
+        } else {
+            Difficulty::Easy
+        }
+    }
+
+    // Change in the number of tiles in their goal position if the empty tile moved in direction
+    // `dir`: 1 if the move places a tile, -1 if it displaces one and 0 otherwise (or if the move
+    // is not legal)
+    pub fn solved_count_delta(&self, dir: Direction) -> isize {
+        if !self.can_slide(dir) {
+            return 0;
+        }
+        let pos = (self.empty as isize + dir.value()) as usize;
+        let tile = self.tiles[pos] as usize;
+        (tile == self.empty + 1) as isize - (tile == pos + 1) as isize
+    }
+
+    // Number of tiles in their goal position at the start of the board, in row-major order,
+    // before the first misplaced one
+    pub fn solved_prefix(&self) -> usize {
+        (0..SIZE * SIZE - 1)
+            .take_while(|&i| self.in_place(i))
+            .count()
+    }
+
+    // The tiles packed four bits each in a u64, the first tile in the lowest bits
+    pub fn to_u64(&self) -> u64 {
+        self.tiles
+            .iter()
+            .enumerate()
+            .fold(0, |packed, (i, &t)| packed | (t as u64) << (4 * i))
+    }
+
+    // Smallest packed form (see `to_u64`) of the board and its mirror image, a compact key
+    // shared by symmetric boards. Rotations are not part of it for the reasons given in
+    // `canonical`.
+    pub fn canonical_u64(&self) -> u64 {
+        self.to_u64().min(self.transposed().to_u64())
+    }
+
+    // Indices of the rows whose tiles are all in their goal positions
+    pub fn solved_rows(&self) -> Vec<usize> {
+        (0..SIZE)
+            .filter(|&r| (0..SIZE).all(|c| self.in_place(r * SIZE + c)))
+            .collect()
+    }
+
+    // Indices of the columns whose tiles are all in their goal positions
+    pub fn solved_columns(&self) -> Vec<usize> {
+        (0..SIZE)
+            .filter(|&c| (0..SIZE).all(|r| self.in_place(r * SIZE + c)))
+            .collect()
+    }
+
+    // Every board obtained by swapping two non-empty tiles. A single swap flips the inversion
+    // parity, so each of them has the opposite solvability of this board.
+    pub fn single_swaps(&self) -> Vec<Board> {
+        let mut boards = Vec::new();
+        for i in 0..self.tiles.len() {
+            for j in i + 1..self.tiles.len() {
+                if i == self.empty || j == self.empty {
+                    continue;
+                }
+                let mut b = self.clone();
+                b.tiles.swap(i, j);
+                boards.push(b);
+            }
+        }
+        boards
+    }
+
+    // Values of the tiles not in their goal position (the empty tile excluded)
+    pub fn misplaced_tiles(&self) -> Vec<u8> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|&(i, &t)| t != 0 && t as usize != i + 1)
+            .map(|(_, &t)| t)
+            .collect()
+    }
+
+    // Render the board like `Display`, marking with an asterisk every misplaced tile
+    pub fn format_diff(&self) -> String {
+        let misplaced = self.misplaced_tiles();
+        self.render_with((0, 1), |t| {
+            if misplaced.contains(&t) {
+                Some(("", "*"))
+            } else {
+                None
+            }
+        })
+    }
+
+    // Render the board like `Display`, wrapping every tile for which `mark` returns some text
+    // in that text. `visible` is how many characters the text before and after a tile takes on
+    // screen (escape sequences take none); the other cells get as many spaces instead, so the
+    // columns stay aligned. Without any marked tile this is the `Display` output.
+    fn render_with<F>(&self, visible: (usize, usize), mark: F) -> String
+    where
+        F: Fn(u8) -> Option<(&'static str, &'static str)>,
+    {
+        let width = Self::cell_width();
+        let (before, after) = if self.tiles.iter().any(|&t| t != 0 && mark(t).is_some()) {
+            visible
+        } else {
+            (0, 0)
+        };
+        let rows: Vec<String> = self
+            .tiles
+            .chunks(SIZE)
+            .map(|row| {
+                let cells: String = row
+                    .iter()
+                    .map(|&t| {
+                        if t == 0 {
+                            return " ".repeat(width + before + after);
+                        }
+                        let pad = " ".repeat(width - t.to_string().len());
+                        match mark(t) {
+                            Some((l, r)) => format!("{}{}{}{}", pad, l, t, r),
+                            None => {
+                                format!("{}{:b$}{}{:a$}", pad, "", t, "", b = before, a = after)
+                            }
+                        }
+                    })
+                    .collect();
+                format!("[{}]", cells)
+            })
+            .collect();
+        rows.join("\n")
+    }
+
+    // Render the tiles in a grid labeled with the row and column indices, like a chessboard.
+    // Columns are as wide as in `Display` and the empty tile is left blank.
+    pub fn to_labeled_string(&self) -> String {
+        let width = Self::cell_width();
+        let header: String = (0..SIZE).map(|c| format!("{:>w$}", c, w = width)).collect();
+        let mut lines = vec![format!(" {}", header)];
+        for (r, row) in self.tiles.chunks(SIZE).enumerate() {
+            let cells: String = row
+                .iter()
+                .map(|&t| match t {
+                    0 => " ".repeat(width),
+                    t => format!("{:>w$}", t, w = width),
+                })
+                .collect();
+            lines.push(format!("{}{}", r, cells));
+        }
+        lines.join("\n")
+    }
+
+    // Render the board like `Display`, with the tiles in their goal position in green. Without
+    // the `color` feature this is the plain `Display` output.
+    pub fn to_colored_string(&self) -> String {
+        if !cfg!(feature = "color") {
+            return self.to_string();
+        }
+        let correct = self.correct_tiles();
+        self.render_with((0, 0), |t| {
+            if correct.contains(&t) {
+                Some(("\x1b[32m", "\x1b[0m"))
+            } else {
+                None
+            }
+        })
+    }
+
+    // Minimum number of arbitrary swaps (of any two tiles, the empty one included) that sort the
+    // board, i.e. the number of tiles minus the number of cycles of the permutation
+    pub fn swap_distance(&self) -> usize {
+        let n = self.tiles.len();
+        // Goal position of the tile at each position
+        let goal = |i: usize| match self.tiles[i] {
+            0 => n - 1,
+            t => t as usize - 1,
+        };
+        let mut seen = [false; SIZE * SIZE];
+        let mut cycles = 0;
+        for start in 0..n {
+            if seen[start] {
+                continue;
+            }
+            cycles += 1;
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                i = goal(i);
+            }
+        }
+        n - cycles
+    }
+}
+
+impl<const N: usize> fmt::Display for Board<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // {:#} prints the tiles in row-major order on a single line
+        if f.alternate() {
+            for (i, t) in self.tiles.iter().enumerate() {
+                if i != 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", t)?;
+            }
+            return Ok(());
+        }
+        // Tiles are right-aligned in columns, the empty tile left blank
+        let width = Self::cell_width();
+        for (i, row) in self.tiles.chunks(N).enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for &t in row {
+                match t {
+                    0 => write!(f, "{:w$}", "", w = width)?,
+                    t => write!(f, "{:>w$}", t, w = width)?,
+                }
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+// Parse a board from whitespace-separated tile values in row-major order, usually `N` rows of
+// `N` numbers
+impl<const N: usize> FromStr for Board<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Board<N>, String> {
+        let mut tiles = Vec::with_capacity(N * N);
+        for tok in s.split_whitespace() {
+            match tok.parse::<usize>() {
+                Ok(t) if t < N * N => tiles.push(t as u8),
+                Ok(t) => return Err(format!("tile {} out of range", t)),
+                Err(_) => return Err(format!("invalid number: {}", tok)),
+            }
+        }
+        if tiles.len() != N * N {
+            return Err(format!("expected {} numbers, got {}", N * N, tiles.len()));
+        }
+        Board::new_from(&tiles).map_err(String::from)
+    }
+}
+
+pub fn count_solvable(boards: &[Board]) -> usize {
+    boards.iter().filter(|b| b.solvable()).count()
+}
+
+// Fraction of `samples` uniformly shuffled boards that are solvable, which tends to 0.5
+pub fn solvable_fraction<R: Rng + ?Sized>(samples: usize, rng: &mut R) -> f64 {
+    if samples == 0 {
+        return 0.0;
+    }
+    let boards: Vec<Board> = (0..samples)
+        .map(|_| {
+            let mut b = Board::new();
+            b.shuffle_with(rng);
+            b
+        })
+        .collect();
+    count_solvable(&boards) as f64 / samples as f64
+}
+
+// Render `board` like its `Display` implementation, with `moved_tile` wrapped in angle brackets
+pub fn render_move(board: &Board, moved_tile: u8) -> String {
+    board.render_with((1, 1), |t| {
+        if t == moved_tile {
+            Some(("<", ">"))
+        } else {
+            None
+        }
+    })
+}
+
+// Move bound to `key`: wasd (either case) or an arrow character, naming the direction the
+// empty tile goes like the solvers do
+pub fn direction_from_key(key: char) -> Option<Direction> {
+    match key {
+        'a' | 'A' | '←' => Some(Direction::Left),
+        'd' | 'D' | '→' => Some(Direction::Right),
+        'w' | 'W' | '↑' => Some(Direction::Up),
+        's' | 'S' | '↓' => Some(Direction::Down),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::HashSet;
+
+    const DEFAULT_CONFIG: &[u8; SIZE * SIZE] =
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+    const SOLVED_CONFIG: &[u8; SIZE * SIZE] =
+        &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    const SOLVABLE_CONFIG: &[u8; SIZE * SIZE] =
+        &[1, 2, 3, 4, 0, 5, 6, 7, 8, 10, 11, 9, 12, 13, 14, 15];
+    const EASY_CONFIG: &[u8; SIZE * SIZE] = &[1, 2, 3, 4, 5, 0, 6, 8, 9, 10, 7, 11, 13, 14, 15, 12];
+
+    #[test]
+    fn dir_all() {
+        let all = Direction::all();
+        for dir in [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ]
+        .iter()
+        {
+            assert_eq!(all.iter().filter(|&d| d == dir).count(), 1);
+        }
+    }
+
+    #[test]
+    fn dir_is_opposite() {
+        assert_eq!(Direction::Up, Direction::Down.opposite());
+        assert_eq!(Direction::Down, Direction::Up.opposite());
+        assert_eq!(Direction::Left, Direction::Right.opposite());
+        assert_eq!(Direction::Right, Direction::Left.opposite());
+
+        assert_ne!(Direction::Up, Direction::Up.opposite());
+        assert_ne!(Direction::Up, Direction::Left.opposite());
+        assert_ne!(Direction::Up, Direction::Right.opposite());
+    }
+
+    #[test]
+    fn dir_opposes() {
+        assert!(Direction::Up.opposites(Direction::Down));
+        assert!(Direction::Down.opposites(Direction::Up));
+        assert!(Direction::Left.opposites(Direction::Right));
+        assert!(Direction::Right.opposites(Direction::Left));
+
+        assert!(!Direction::Up.opposites(Direction::Up));
+        assert!(!Direction::Up.opposites(Direction::Left));
+        assert!(!Direction::Up.opposites(Direction::Right));
+    }
+
+    #[test]
+    fn dir_horizontal() {
+        assert!(Direction::Left.horizontal());
+        assert!(Direction::Right.horizontal());
+        assert!(!Direction::Up.horizontal());
+        assert!(!Direction::Down.horizontal());
+    }
+
+    #[test]
+    fn dir_from_str() {
+        assert_eq!("Left".parse::<Direction>(), Ok(Direction::Left));
+        assert_eq!("right".parse::<Direction>(), Ok(Direction::Right));
+        assert_eq!("U".parse::<Direction>(), Ok(Direction::Up));
+        assert_eq!("DOWN".parse::<Direction>(), Ok(Direction::Down));
+        assert!("sideways".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn dir_rotated() {
+        assert_eq!(Direction::Up.rotated(Rotation::Deg90), Direction::Right);
+        assert_eq!(Direction::Up.rotated(Rotation::Deg180), Direction::Down);
+        assert_eq!(Direction::Up.rotated(Rotation::Deg270), Direction::Left);
+        assert_eq!(Direction::Left.rotated(Rotation::Deg90), Direction::Up);
+    }
+
+    #[test]
+    fn dir_value() {
+        assert_eq!(Direction::Up.value(), -4);
+        assert_eq!(Direction::Down.value(), 4);
+        assert_eq!(Direction::Left.value(), -1);
+        assert_eq!(Direction::Right.value(), 1);
+    }
+
+    fn is_board_valid(b: &Board) -> bool {
+        let mut tiles = HashSet::new();
+        for tile in &b.tiles {
+            tiles.insert(tile);
+        }
+        if tiles.len() != b.tiles.len() {
+            return false;
+        }
+        for tile in &tiles {
+            let value = **tile as usize;
+            if value >= b.tiles.len() {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn create_board() {
+        let b: Board = Board::new();
+        assert_eq!(b.tiles, *DEFAULT_CONFIG);
+        assert_eq!(b.empty, 0);
+
+        let b: Board = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert_eq!(b.tiles, *DEFAULT_CONFIG);
+        assert_eq!(b.empty, 0);
+
+        let b: Board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.tiles, *SOLVED_CONFIG);
+        assert_eq!(b.empty, 15);
+    }
+
+    #[test]
+    fn board_with_blank_at() {
+        for i in 0..SIZE * SIZE {
+            let b = Board::with_blank_at(i);
+            assert_eq!(b.empty, i);
+            assert_eq!(b.tiles[i], 0);
+            assert!(is_board_valid(&b));
+        }
+        assert_eq!(Board::with_blank_at(0).tiles, *DEFAULT_CONFIG);
+        assert_eq!(Board::with_blank_at(15).tiles, *SOLVED_CONFIG);
+    }
+
+    #[test]
+    fn board_from_shuffled() {
+        let b = Board::from_shuffled(SOLVED_CONFIG.to_vec()).expect("failed to create board");
+        assert_eq!(b.tiles, *SOLVED_CONFIG);
+        assert_eq!(b.empty, 15);
+
+        let dup = SOLVED_CONFIG.iter().map(|&t| if t == 15 { 14 } else { t });
+        assert!(Board::from_shuffled(dup).is_err());
+    }
+
+    #[test]
+    fn parse_board() {
+        let b = Board::from_str_tiles("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 0\n")
+            .expect("failed to parse solved board");
+        assert_eq!(b.tiles, *SOLVED_CONFIG);
+        assert_eq!(b.empty, 15);
+
+        assert!(Board::from_str_tiles("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15").is_err());
+        assert!(Board::from_str_tiles("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 x").is_err());
+        assert!(Board::from_str_tiles("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 16").is_err());
+    }
+
+    #[test]
+    fn parse_board_from_str() {
+        let b: Board = "1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 0"
+            .parse()
+            .expect("failed to parse solved board");
+        assert!(b.solved());
+        let b: Board = "\n1 2 3 4 \n5 6 7 8\n\n9 10 11 12  \n13 14 15 0\n\n"
+            .parse()
+            .expect("failed to parse solved board");
+        assert!(b.solved());
+        let b: Board<3> = "1 2 3\n4 5 6\n7 8 0"
+            .parse()
+            .expect("failed to parse 3x3 board");
+        assert!(b.solved());
+
+        let parse = |s: &str| s.parse::<Board>();
+        assert_eq!(
+            parse("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15"),
+            Err("expected 16 numbers, got 15".to_string())
+        );
+        assert_eq!(
+            parse("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 42 0"),
+            Err("tile 42 out of range".to_string())
+        );
+        assert_eq!(
+            parse("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 x"),
+            Err("invalid number: x".to_string())
+        );
+        assert!(parse("1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 14 0").is_err());
+    }
+
+    #[test]
+    fn board_solve() {
+        let b: Board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.solve().ok(), Astar::run(&b));
+        let b: Board = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert_eq!(b.solve(), Err(SolveError::Unsolvable));
+    }
+
+    #[test]
+    fn board_from_iter() {
+        let b: Board = Board::new_from_iter(SOLVED_CONFIG.iter().cloned(), SIZE)
+            .expect("failed to create solved board");
+        assert_eq!(b, Board::new_from(SOLVED_CONFIG).unwrap());
+        assert!(Board::<SIZE>::new_from_iter(SOLVED_CONFIG.iter().cloned(), 3).is_err());
+
+        assert!(Board::<SIZE>::new_from_iter(SOLVED_CONFIG[..15].iter().cloned(), SIZE).is_err());
+        assert!(
+            Board::<SIZE>::new_from_iter(SOLVED_CONFIG.iter().cloned().chain(Some(1)), SIZE)
+                .is_err()
+        );
+        // Stops at the first bad value instead of consuming everything
+        let mut iter = [1, 16, 2, 3].iter().cloned();
+        assert!(Board::<SIZE>::new_from_iter(&mut iter, SIZE).is_err());
+        assert_eq!(iter.next(), Some(2));
+
+        let small = Board::<3>::new_from_iter([1, 2, 3, 4, 5, 6, 7, 8, 0].iter().cloned(), 3)
+            .expect("failed to create 8-puzzle board");
+        assert!(small.solved());
+        assert!(Board::<3>::new_from_iter([1, 2, 3, 9].iter().cloned(), 3).is_err());
+    }
+
+    #[test]
+    fn board_from_partial() {
+        let full: Vec<Option<u8>> = SOLVED_CONFIG.iter().map(|&t| Some(t)).collect();
+        let b = Board::from_partial(&full).expect("failed to create solved board");
+        assert!(b.solved());
+
+        let mut spec = vec![None; SIZE * SIZE];
+        spec[0] = Some(15);
+        spec[5] = Some(0);
+        let b = Board::from_partial(&spec).expect("failed to create board");
+        assert_eq!(
+            b.tiles,
+            [15, 1, 2, 3, 4, 0, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+        );
+        assert_eq!(b.empty, 5);
+
+        spec[1] = Some(15);
+        assert!(Board::from_partial(&spec).is_err());
+        spec[1] = Some(16);
+        assert!(Board::from_partial(&spec).is_err());
+        assert!(Board::from_partial(&spec[..15]).is_err());
+    }
+
+    #[test]
+    fn board_from_reader() {
+        let input: &[u8] = b"1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 15 0\n";
+        let b = Board::from_reader(input).expect("failed to read solved board");
+        assert_eq!(b.tiles, *SOLVED_CONFIG);
+
+        let invalid: &[u8] = &[0xff, 0xfe];
+        assert!(Board::from_reader(invalid).is_err());
+
+        // Only the first board is consumed
+        let mut input: &[u8] = b"1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 0\n\n2 1 3";
+        let b = Board::from_reader(&mut input).expect("failed to read first board");
+        assert_eq!(b.tiles, *SOLVED_CONFIG);
+        assert_eq!(input, b"\n2 1 3");
+    }
+
+    #[test]
+    fn parse_board_regressions() {
+        // Used to index past the end of the tile array
+        let tiles = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0, 1];
+        assert!(Board::<SIZE>::new_from(&tiles).is_err());
+        assert!(Board::from_str_tiles("1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 0 1").is_err());
+    }
+
+    #[test]
+    fn fuzz_parser() {
+        let mut rng = thread_rng();
+        for _ in 0..10_000 {
+            let len = rng.gen_range(0, 40);
+            let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let _ = Board::<SIZE>::new_from(&bytes);
+            let _ = Board::from_str_tiles(&String::from_utf8_lossy(&bytes));
+
+            // Bias towards inputs that look like boards
+            let text: Vec<String> = (0..len)
+                .map(|_| rng.gen_range(0u8, 20).to_string())
+                .collect();
+            let _ = Board::from_str_tiles(&text.join(" "));
+            let small: Vec<u8> = (0..len).map(|_| rng.gen_range(0, 20)).collect();
+            let _ = Board::<SIZE>::new_from(&small);
+        }
+    }
+
+    #[test]
+    fn board_move() {
+        let mut b = Board::new();
+
+        // Move all the way to the right
+        for i in 0..3 {
+            assert!(b.can_slide(Direction::Right));
+            b.slide(Direction::Right);
+            assert!(is_board_valid(&b));
+            assert_eq!(b.empty, i + 1);
+        }
+        // Try to go over the right edge
+        assert!(!b.can_slide(Direction::Right));
+        let res = b.slide_safe(Direction::Right);
+        assert!(res.is_err());
+        assert!(is_board_valid(&b));
+        assert_eq!(b.empty, 3);
+
+        // Move all the way down
+        for i in 0..3 {
+            assert!(b.can_slide(Direction::Down));
+            b.slide(Direction::Down);
+            assert!(is_board_valid(&b));
+            assert_eq!(b.empty, (i + 1) * SIZE + 3);
+        }
+        // Try to go over the bottom edge
+        assert!(!b.can_slide(Direction::Down));
+        let res = b.slide_safe(Direction::Down);
+        assert!(res.is_err());
+        assert!(is_board_valid(&b));
+        assert_eq!(b.empty, 15);
+
+        // Move all the way to the left
+        for i in 0..3 {
+            assert!(b.can_slide(Direction::Left));
+            b.slide(Direction::Left);
+            assert!(is_board_valid(&b));
+            assert_eq!(b.empty, b.tiles.len() - 1 - i - 1);
+        }
+        // Try to go over the left edge
+        assert!(!b.can_slide(Direction::Left));
+        let res = b.slide_safe(Direction::Left);
+        assert!(res.is_err());
+        assert!(is_board_valid(&b));
+        assert_eq!(b.empty, 12);
+
+        // Move all the way up
+        for i in 0..3 {
+            assert!(b.can_slide(Direction::Up));
+            b.slide(Direction::Up);
+            assert!(is_board_valid(&b));
+            assert_eq!(b.empty, (3 - i - 1) * SIZE);
+        }
+        // Try to go over the top edge
+        assert!(!b.can_slide(Direction::Up));
+        let res = b.slide_safe(Direction::Up);
+        assert!(res.is_err());
+        assert!(is_board_valid(&b));
+        assert_eq!(b.empty, 0);
+    }
+
+    #[test]
+    fn board_slide_safe_moved_tile() {
+        let mut b: Board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.slide_safe(Direction::Right), Ok(6));
+        assert_eq!(b.slide_safe(Direction::Down), Ok(7));
+        assert_eq!(b.slide_safe(Direction::Left), Ok(10));
+        assert_eq!(b.slide_safe(Direction::Up), Ok(6));
+        assert!(b.slide_safe(Direction::Up).is_ok());
+        assert!(b.slide_safe(Direction::Up).is_err());
+    }
+
+    #[test]
+    fn board_try_slide() {
+        let mut b: Board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.try_slide(Direction::Up), Some(Direction::Up));
+        assert_eq!(b.empty_index(), 1);
+        assert_eq!(b.try_slide(Direction::Up), None);
+        assert_eq!(b.empty_index(), 1);
+    }
+
+    #[test]
+    fn board_neighbours() {
+        assert_eq!(Board::<SIZE>::neighbours(0), vec![4, 1]);
+        assert_eq!(Board::<SIZE>::neighbours(6), vec![2, 10, 5, 7]);
+        assert_eq!(Board::<3>::neighbours(8), vec![5, 7]);
+        assert_eq!(Board::<2>::neighbours(1), vec![3, 0]);
+    }
+
+    #[test]
+    fn board_move_count() {
+        // Corners
+        for &corner in &[0, 3, 12, 15] {
+            let mut tiles = *DEFAULT_CONFIG;
+            tiles.swap(0, corner);
+            let b = Board::new_from(&tiles).expect("failed to create board");
+            assert_eq!(b.move_count(), 2);
+        }
+        // Edges
+        for &edge in &[1, 2, 4, 7, 8, 11, 13, 14] {
+            let mut tiles = *DEFAULT_CONFIG;
+            tiles.swap(0, edge);
+            let b = Board::new_from(&tiles).expect("failed to create board");
+            assert_eq!(b.move_count(), 3);
+        }
+        // Interior
+        for &inner in &[5, 6, 9, 10] {
+            let mut tiles = *DEFAULT_CONFIG;
+            tiles.swap(0, inner);
+            let b = Board::new_from(&tiles).expect("failed to create board");
+            assert_eq!(b.move_count(), 4);
+        }
+    }
+
+    #[test]
+    fn board_movable_tiles() {
+        assert_eq!(Board::tile_index_at(0, 0), 0);
+        assert_eq!(Board::tile_index_at(2, 1), 9);
+        assert_eq!(Board::tile_index_at(3, 3), 15);
+
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let mut movable = b.movable_tiles();
+        movable.sort_unstable();
+        assert_eq!(movable, vec![12, 15]);
+
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let mut movable = b.movable_tiles();
+        movable.sort_unstable();
+        assert_eq!(movable, vec![2, 5, 6, 10]);
+    }
+
+    #[test]
+    fn random_board() {
+        let b = Board::new_random();
+        assert!(is_board_valid(&b));
+
+        let mut b = Board::new();
+        assert!(is_board_valid(&b));
+        b.shuffle();
+        assert!(is_board_valid(&b));
+    }
+
+    #[test]
+    fn random_board_displacement() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.displacement_sum(), 0);
+
+        // A uniformly shuffled 4x4 board averages about 2.5 moves away per tile
+        let samples = 500;
+        let total: usize = (0..samples)
+            .map(|_| Board::new_random().displacement_sum())
+            .sum();
+        let average = total as f64 / samples as f64;
+        assert!(average > 30.0, "average displacement too low: {}", average);
+    }
+
+    #[test]
+    fn board_solvable() {
+        let b: Board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert!(b.solvable());
+
+        let b: Board = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert!(!b.solvable());
+
+        let b: Board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert!(b.solvable());
+    }
+
+    #[test]
+    fn board_correct_tiles() {
+        let tiles = [1, 2, 3, 4, 6, 5, 8, 7, 10, 9, 12, 11, 14, 13, 0, 15];
+        let b = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(b.correct_tiles(), vec![1, 2, 3, 4]);
+
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.correct_tiles(), (1..16).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn board_custom_goal() {
+        let goal: Board = Board::goal();
+        assert!(goal.solved());
+        assert_eq!(goal, Board::new_from(SOLVED_CONFIG).unwrap());
+
+        let custom = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert!(!custom.solved());
+        assert!(custom.solved_to(&custom));
+        assert!(!goal.solved_to(&custom));
+    }
+
+    #[test]
+    fn board_seeded() {
+        for seed in 0..20 {
+            let b = Board::new_seeded(seed);
+            assert!(is_board_valid(&b));
+            assert!(b.solvable());
+            assert_eq!(b, Board::new_seeded(seed));
+        }
+    }
+
+    #[test]
+    fn board_new_random() {
+        for _ in 0..200 {
+            let b = Board::new_random();
+            assert!(is_board_valid(&b));
+            assert!(b.solvable());
+        }
+    }
+
+    #[test]
+    fn board_solvable_stream() {
+        let boards: Vec<Board> = Board::solvable_stream(7).take(10).collect();
+        assert!(boards.iter().all(|b| is_board_valid(b) && b.solvable()));
+        let distinct: HashSet<u64> = boards.iter().map(|b| b.to_u64()).collect();
+        assert_eq!(distinct.len(), 10);
+        assert_eq!(
+            boards,
+            Board::solvable_stream(7).take(10).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn board_daily() {
+        let today = Board::daily((2024, 3, 14));
+        assert!(today.solvable());
+        assert_eq!(today, Board::daily((2024, 3, 14)));
+        assert_ne!(today, Board::daily((2024, 3, 15)));
+        assert_ne!(today, Board::daily((2025, 3, 14)));
+    }
+
+    #[test]
+    fn board_can_place_without_disturbing() {
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert!(b.can_place_without_disturbing(7, 6, &[]));
+        assert!(b.can_place_without_disturbing(15, 0, &[]));
+        assert!(!b.can_place_without_disturbing(0, 0, &[]));
+
+        // Freezing the whole first row keeps the 15 away from the top-left corner
+        assert!(!b.can_place_without_disturbing(15, 0, &[0, 1, 2, 3]));
+        // Freezing the cells around the empty tile blocks everything
+        assert!(!b.can_place_without_disturbing(7, 6, &[1, 4, 6, 9]));
+        assert!(b.can_place_without_disturbing(7, 6, &[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn board_transposed() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.transposed(), b);
+        assert_eq!(b.canonical(), b);
+
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let t = b.transposed();
+        assert!(is_board_valid(&t));
+        assert_eq!(t.transposed(), b);
+        assert_eq!(t.canonical(), b.canonical());
+        // Transposed moves solve the mirror image
+        let mut t = t;
+        for dir in [
+            Direction::Right,
+            Direction::Down,
+            Direction::Right,
+            Direction::Down,
+        ]
+        .iter()
+        {
+            assert!(t.slide_safe(dir.transposed()).is_ok());
+        }
+        assert!(t.solved());
+    }
+
+    #[test]
+    fn board_symmetry_eq() {
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert!(b.symmetry_eq(&b));
+        assert!(b.symmetry_eq(&b.transposed()));
+        assert!(b.transposed().symmetry_eq(&b));
+        let other = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert!(!b.symmetry_eq(&other));
+        assert!(!b.symmetry_eq(&other.transposed()));
+
+        // Turning the solved board by 90 degrees moves the empty tile to another corner, so the
+        // result is a different puzzle that needs moves to be solved
+        let goal: Board = Board::goal();
+        let turn = |i: usize| (i % SIZE) * SIZE + SIZE - 1 - i / SIZE;
+        let mut tiles = [0; SIZE * SIZE];
+        for (i, &t) in goal.tiles().iter().enumerate() {
+            tiles[turn(i)] = t;
+        }
+        let rotated = Board::new_from(&tiles).expect("failed to create rotated board");
+        assert_eq!(goal.rotated(Rotation::Deg90), rotated);
+        assert!(!rotated.solved());
+        assert!(!goal.symmetry_eq(&rotated));
+        assert_ne!(goal.canonical_u64(), rotated.canonical_u64());
+    }
+
+    #[test]
+    fn board_rotation_eq() {
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert!(b.rotation_eq(&b));
+        let mut turned = b.clone();
+        for _ in 0..4 {
+            turned = turned.rotated(Rotation::Deg90);
+            assert!(b.rotation_eq(&turned));
+            assert!(turned.rotation_eq(&b));
+        }
+        assert_eq!(turned, b);
+        assert_eq!(
+            b.rotated(Rotation::Deg90).rotated(Rotation::Deg180),
+            b.rotated(Rotation::Deg270)
+        );
+        // Flipped over, without renumbering the tiles as `transposed` does
+        let flip = |i: usize| (i % SIZE) * SIZE + i / SIZE;
+        let mut tiles = [0; SIZE * SIZE];
+        for (i, &t) in b.tiles().iter().enumerate() {
+            tiles[flip(i)] = t;
+        }
+        let flipped = Board::new_from(&tiles).expect("failed to create flipped board");
+        assert!(b.rotation_eq(&flipped));
+        assert!(b.rotation_eq(&flipped.rotated(Rotation::Deg270)));
+
+        let other = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert!(!b.rotation_eq(&other));
+    }
+
+    #[test]
+    fn keypresses() {
+        assert_eq!(direction_from_key('a'), Some(Direction::Left));
+        assert_eq!(direction_from_key('d'), Some(Direction::Right));
+        assert_eq!(direction_from_key('w'), Some(Direction::Up));
+        assert_eq!(direction_from_key('s'), Some(Direction::Down));
+        assert_eq!(direction_from_key('W'), Some(Direction::Up));
+        assert_eq!(direction_from_key('←'), Some(Direction::Left));
+        assert_eq!(direction_from_key('→'), Some(Direction::Right));
+        assert_eq!(direction_from_key('↑'), Some(Direction::Up));
+        assert_eq!(direction_from_key('↓'), Some(Direction::Down));
+        assert_eq!(direction_from_key('x'), None);
+        assert_eq!(direction_from_key(' '), None);
+
+        let mut b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        for key in "dsds".chars() {
+            assert!(b.apply_keypress(key).is_ok());
+        }
+        assert!(b.solved());
+        assert!(b.apply_keypress('s').is_err());
+        assert!(b.apply_keypress('q').is_err());
+        assert!(b.solved());
+    }
+
+    #[test]
+    fn board_successors_excluding() {
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let all = b.successors_excluding(|_| false);
+        assert_eq!(all.len(), 4);
+        for (next, dir) in all.iter() {
+            let mut expected = b.clone();
+            expected.slide(*dir);
+            assert_eq!(*next, expected);
+        }
+
+        let mut right = b.clone();
+        right.slide(Direction::Right);
+        let rest = b.successors_excluding(|next| *next == right);
+        assert_eq!(rest.len(), 3);
+        assert!(rest.iter().all(|&(_, dir)| dir != Direction::Right));
+    }
+
+    #[test]
+    fn board_legal_mask() {
+        assert_eq!(Board::with_blank_at(5).legal_mask(), 0b1111);
+        let corner = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let mask = corner.legal_mask();
+        assert_eq!(mask.count_ones(), 2);
+        for &dir in Direction::all().iter() {
+            assert_eq!(mask & dir.bit() != 0, corner.can_slide(dir));
+        }
+        assert_eq!(mask, Direction::Left.bit() | Direction::Up.bit());
+    }
+
+    #[test]
+    fn board_solved_count_delta() {
+        // The 6 goes home moving right, the others leave home
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.solved_count_delta(Direction::Right), 1);
+        assert_eq!(b.solved_count_delta(Direction::Up), -1);
+        assert_eq!(b.solved_count_delta(Direction::Down), -1);
+        assert_eq!(b.solved_count_delta(Direction::Left), -1);
+        let solved = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(solved.solved_count_delta(Direction::Down), 0);
+        assert_eq!(solved.solved_count_delta(Direction::Left), -1);
+    }
+
+    #[test]
+    fn board_solved_prefix() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.solved_prefix(), 15);
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.solved_prefix(), 5);
+        let b = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert_eq!(b.solved_prefix(), 0);
+    }
+
+    #[test]
+    fn board_canonical_u64() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.to_u64(), 0x0fed_cba9_8765_4321);
+        assert_eq!(b.canonical_u64(), b.to_u64());
 
         let b = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        let mirror = b.transposed();
+        assert_ne!(b.to_u64(), mirror.to_u64());
+        assert_eq!(b.canonical_u64(), mirror.canonical_u64());
+        assert_ne!(b.canonical_u64(), Board::new().canonical_u64());
+    }
+
+    #[test]
+    fn board_solved_rows_and_columns() {
+        let tiles = [1, 2, 3, 4, 6, 5, 8, 7, 10, 9, 12, 11, 14, 13, 0, 15];
+        let b = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(b.solved_rows(), vec![0]);
+        assert!(b.solved_columns().is_empty());
+
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.solved_rows(), vec![0, 1, 2, 3]);
+        assert_eq!(b.solved_columns(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn board_shuffle_tracked() {
+        let mut rng = thread_rng();
+        let original = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let mut b = original.clone();
+        let perm = b.shuffle_tracked(&mut rng);
+        assert!(is_board_valid(&b));
+        assert_eq!(b.tiles[b.empty], 0);
+
+        let mut restored = b.clone();
+        for (i, &p) in perm.iter().enumerate() {
+            restored.tiles[p] = b.tiles[i];
+        }
+        restored.normalize();
+        assert_eq!(restored, original);
+    }
+
+    #[test]
+    fn board_require_all_directions() {
+        let mut rng = thread_rng();
+        for _ in 0..3 {
+            let b = Board::require_all_directions(&mut rng);
+            let moves = b.solve().expect("no solution found");
+            for dir in Direction::all().iter() {
+                assert!(moves.contains(dir), "{} missing from {:?}", dir, moves);
+            }
+        }
+    }
+
+    #[test]
+    fn board_difficulty_label() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.difficulty_label(), Difficulty::Easy);
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.difficulty_label(), Difficulty::Easy);
+
+        let b = Board::scramble_to_heuristic(30, &mut thread_rng());
+        assert!(b.difficulty_label() >= Difficulty::Hard);
+
+        let strict = DifficultyThresholds {
+            medium: 1,
+            hard: 2,
+            expert: 3,
+        };
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.difficulty_label_with(&strict), Difficulty::Expert);
+    }
+
+    #[test]
+    fn board_bottleneck_move() {
+        // The first three moves only take the heuristic from 6 to 5, after which each of the
+        // five moves left has to bring a tile closer
+        let tiles = [1, 2, 3, 4, 5, 6, 11, 7, 9, 10, 0, 12, 13, 14, 8, 15];
+        let b = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(b.solve().map(|moves| moves.len()), Ok(8));
+        assert_eq!(b.manhattan_cost(), 6);
+        assert_eq!(b.bottleneck_move(), Some(3));
+
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.bottleneck_move(), None);
+        let b = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert_eq!(b.bottleneck_move(), None);
+    }
+
+    #[test]
+    fn board_scramble_to_heuristic() {
+        let mut rng = thread_rng();
+        for &target in &[0, 1, 10, 25] {
+            let b = Board::scramble_to_heuristic(target, &mut rng);
+            assert!(is_board_valid(&b));
+            assert!(b.solvable());
+            // Each move changes the heuristic by exactly one
+            assert_eq!(b.manhattan_cost(), target);
+        }
+    }
+
+    #[test]
+    fn board_single_swaps() {
+        for config in &[SOLVED_CONFIG, DEFAULT_CONFIG, SOLVABLE_CONFIG] {
+            let b = Board::new_from(*config).expect("failed to create board");
+            let swaps = b.single_swaps();
+            assert_eq!(swaps.len(), 15 * 14 / 2);
+            for s in swaps.iter() {
+                assert!(is_board_valid(s));
+                assert_eq!(s.empty, b.empty);
+                assert_ne!(s.solvable(), b.solvable());
+            }
+        }
+    }
+
+    #[test]
+    fn board_random_walk() {
+        let mut rng = thread_rng();
+        let start = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let walk = start.random_walk(50, &mut rng);
+        assert_eq!(walk.len(), 50);
+        assert_eq!(walk[0].0, start);
+        for (i, (b, dir)) in walk.iter().enumerate() {
+            assert!(is_board_valid(b));
+            assert!(b.can_slide(*dir));
+            if i > 0 {
+                assert!(!walk[i - 1].1.opposites(*dir));
+            }
+            if let Some((next, _)) = walk.get(i + 1) {
+                let mut b = b.clone();
+                b.slide(*dir);
+                assert_eq!(b, *next);
+            }
+        }
+    }
+
+    #[test]
+    fn board_misplaced_tiles() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert!(b.misplaced_tiles().is_empty());
+
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.misplaced_tiles(), vec![6, 7, 11, 12]);
+    }
+
+    #[test]
+    fn board_empty_in_sync() {
+        let mut rng = thread_rng();
+        let mut b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let mut last = None;
+        for i in 0..100_000 {
+            let dir = b.random_move(last, &mut rng);
+            if i % 2 == 0 {
+                b.slide(dir);
+            } else {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert_eq!(b.tiles[b.empty], 0);
+            last = Some(dir);
+        }
+        assert!(is_board_valid(&b));
+    }
+
+    #[test]
+    fn board_manhattan_lower_bound() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.manhattan_lower_bound(), 0);
+
+        let mut rng = thread_rng();
+        for _ in 0..50 {
+            let mut b = Board::new();
+            b.shuffle_with(&mut rng);
+            assert!(b.manhattan_lower_bound() <= Astar::linear_conflict(&b));
+        }
+    }
+
+    #[test]
+    fn board_distance_map() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.distance_map(), [0; SIZE * SIZE]);
+
+        let tiles = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+        let b = Board::new_from(&tiles).expect("failed to create board");
+        let mut expected = [0; SIZE * SIZE];
+        expected[15] = 1;
+        assert_eq!(b.distance_map(), expected);
+        assert_eq!(b.distance_map().iter().sum::<usize>(), b.manhattan_cost());
+    }
+
+    #[test]
+    fn board_moves_from_solved_estimate() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.moves_from_solved_estimate(), 0);
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.moves_from_solved_estimate(), 4);
+    }
+
+    #[test]
+    fn board_labeled_string() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let labeled = b.to_labeled_string();
+        assert_eq!(labeled.lines().next(), Some("   0  1  2  3"));
+        assert_eq!(labeled.lines().nth(3), Some("2  9 10 11 12"));
+        assert_eq!(labeled.lines().nth(4), Some("3 13 14 15   "));
+        assert_eq!(labeled.lines().count(), SIZE + 1);
+    }
+
+    #[test]
+    fn board_colored_string() {
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        if cfg!(feature = "color") {
+            assert!(b.to_colored_string().contains("\x1b[32m1\x1b[0m"));
+            assert!(!b.to_colored_string().contains("\x1b[32m6"));
+            let plain = b
+                .to_colored_string()
+                .replace("\x1b[32m", "")
+                .replace("\x1b[0m", "");
+            assert_eq!(plain, b.to_string());
+        } else {
+            assert_eq!(b.to_colored_string(), b.to_string());
+        }
+    }
+
+    #[test]
+    fn board_format_diff() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert!(!b.format_diff().contains('*'));
+        assert_eq!(b.format_diff(), b.to_string());
+
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(
+            b.format_diff(),
+            "[  1   2   3   4 ]\n[  5       6*  8 ]\n[  9  10   7* 11*]\n[ 13  14  15  12*]"
+        );
+    }
+
+    #[test]
+    fn board_swap_distance() {
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(b.swap_distance(), 0);
+
+        let mut tiles = *SOLVED_CONFIG;
+        tiles.swap(0, 1);
+        let b = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(b.swap_distance(), 1);
+
+        // 6 -> 7 -> 11 -> 12 -> empty form a single cycle
+        let b = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(b.swap_distance(), 4);
+
+        // Every tile is one position off
+        let b = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert_eq!(b.swap_distance(), 15);
+    }
+
+    #[test]
+    fn board_parity_class() {
+        let solved: Board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let mut b = solved.clone();
+        let dirs = [
+            Direction::Up,
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+        ];
+        for &dir in dirs.iter().cycle().take(20) {
+            b.slide(dir);
+            assert_eq!(b.parity_class(), solved.parity_class());
+        }
+
+        let b: Board = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert_ne!(b.parity_class(), solved.parity_class());
+    }
+
+    #[test]
+    fn board_with_inversions() {
+        let mut rng = thread_rng();
+        for &target in [0, 1, 2, 17, 50, 104, 105].iter() {
+            let b = Board::with_inversions(target, &mut rng).expect("failed to create board");
+            assert!(is_board_valid(&b));
+            assert_eq!(b.inversions(), target);
+            assert_eq!(b.solvable(), target % 2 == 0);
+        }
+        assert_eq!(Board::with_inversions(0, &mut rng), Some(Board::goal()));
+        assert_eq!(Board::with_inversions(106, &mut rng), None);
+    }
+
+    #[test]
+    fn board_count_solvable() {
+        let boards: Vec<Board> = [SOLVED_CONFIG, DEFAULT_CONFIG, SOLVABLE_CONFIG]
+            .iter()
+            .map(|c| Board::new_from(*c).expect("failed to create board"))
+            .collect();
+        assert_eq!(count_solvable(&boards), 2);
+        assert_eq!(count_solvable(&[]), 0);
+    }
+
+    #[test]
+    fn board_solvable_fraction() {
+        let fraction = solvable_fraction(4000, &mut thread_rng());
+        // The standard deviation is about 0.008
+        assert!((fraction - 0.5).abs() < 0.05, "fraction: {}", fraction);
+    }
+
+    #[test]
+    fn board_solved() {
+        let b: Board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert!(b.solved());
+
+        let b: Board = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert!(!b.solved());
+
+        let b: Board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert!(!b.solved());
+    }
+
+    #[test]
+    fn board_minimal_scramble() {
+        let target = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let moves = target
+            .minimal_scramble()
+            .expect("scramble should not be None");
+        let mut b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        for &dir in moves.iter() {
+            assert!(b.slide_safe(dir).is_ok());
+        }
+        assert_eq!(b, target);
+        let solution = Astar::run(&target).expect("solution should not be None");
+        assert_eq!(moves.len(), solution.len());
+    }
+
+    #[test]
+    fn board_display() {
+        let b: Board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(
+            format!("{}", b),
+            "[  1  2  3  4]\n[  5  6  7  8]\n[  9 10 11 12]\n[ 13 14 15   ]"
+        );
+        let default: Board =
+            Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        assert_eq!(
+            format!("{}", default),
+            "[     1  2  3]\n[  4  5  6  7]\n[  8  9 10 11]\n[ 12 13 14 15]"
+        );
+        assert_eq!(format!("{:#}", b), "1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 0");
+    }
+
+    #[test]
+    fn board_other_sizes() {
+        let b = Board::<3>::new_from(&[1, 2, 3, 4, 5, 6, 7, 8, 0]).expect("failed to create board");
+        assert!(b.solved());
+        assert!(b.solvable());
+        assert_eq!(b, Board::<3>::goal());
+        assert_eq!(format!("{}", b), "[ 1 2 3]\n[ 4 5 6]\n[ 7 8  ]");
+        assert!(Board::<3>::new_from(SOLVED_CONFIG).is_err());
+        assert!(Board::<3>::new_from(&[1, 2, 3, 4, 5, 6, 7, 0]).is_err());
+        assert_eq!(Board::<3>::new().tiles(), &[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let mut b =
+            Board::<3>::new_from(&[1, 2, 3, 4, 5, 6, 0, 7, 8]).expect("failed to create board");
+        assert!(!b.can_slide(Direction::Left));
+        assert!(!b.can_slide(Direction::Down));
+        assert_eq!(b.slide_safe(Direction::Up), Ok(4));
+        assert_eq!(b.slide_safe(Direction::Down), Ok(4));
+        assert_eq!(b.slide_safe(Direction::Right), Ok(7));
+        assert_eq!(b.slide_safe(Direction::Right), Ok(8));
+        assert!(b.solved());
+
+        // On boards of odd width the row of the empty tile does not matter
+        let b = Board::<3>::new_from(&[2, 1, 3, 4, 5, 6, 7, 8, 0]).expect("failed to create board");
+        assert!(!b.solvable());
+        let b = Board::<3>::new_from(&[1, 2, 3, 0, 4, 6, 7, 5, 8]).expect("failed to create board");
+        assert!(b.solvable());
+
+        let mut b = Board::<5>::goal();
+        assert_eq!(b.tiles().len(), 25);
+        b.slide(Direction::Up);
+        assert_eq!(b.empty_index(), 19);
+        assert!(b.solvable() && !b.solved());
+
+        let tiles: Vec<u8> = (1..100).chain(Some(0)).collect();
+        let b = Board::<10>::new_from(&tiles).expect("failed to create board");
+        assert!(b.solved());
+        assert_eq!(Board::<10>::new().empty_index(), 0);
+    }
+
+    #[test]
+    fn board_ord() {
+        let solved: Board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let default = Board::new_from(DEFAULT_CONFIG).expect("failed to create default board");
+        let solvable = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert!(default < solvable);
+        assert!(solvable < solved);
+
+        let mut boards = vec![solved.clone(), default.clone(), solvable.clone()];
+        boards.sort();
+        assert_eq!(boards, vec![default, solvable, solved]);
+    }
+
+    #[test]
+    fn board_render_move() {
+        let mut b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        b.slide(Direction::Left);
+        // Tile 15 slid right, next to where the empty tile now sits
+        let moved = b.tiles[b.empty + 1];
+        assert_eq!(moved, 15);
+        assert_eq!(
+            render_move(&b, moved),
+            "[   1    2    3    4 ]\n[   5    6    7    8 ]\n[   9   10   11   12 ]\n[  13   14       <15>]"
+        );
+    }
+
+    #[test]
+    fn board_checksum() {
+        let a = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(a.checksum(), b.checksum());
+        assert_eq!(a.checksum(), 0x9e8e_6805);
+
+        let c = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert_ne!(a.checksum(), c.checksum());
+    }
+
+    #[test]
+    fn board_normalize() {
+        let mut b = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert!(!b.normalize());
+        assert_eq!(b.empty, 15);
+
+        b.empty = 3;
         assert!(!b.solved());
+        assert!(b.normalize());
+        assert_eq!(b.empty, 15);
+        assert!(b.solved());
+        assert!(!b.normalize());
     }
 
     #[test]
     fn board_clone() {
-        let b = Board::new();
+        let b: Board = Board::new();
         let clone1 = b.clone();
-        let clone2 = Board::new_from(&b.tiles).expect("failed to create clone board");
+        let clone2: Board = Board::new_from(&b.tiles).expect("failed to create clone board");
         assert_eq!(b.tiles, clone1.tiles);
         assert_ne!(b.tiles.as_ptr(), clone1.tiles.as_ptr());
         assert_eq!(b.empty, clone1.empty);
@@ -0,0 +1,145 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::board::*;
+use crate::solver::*;
+
+// Longest optimal solution for which breadth-first search is still attempted
+const BFS_MAX_MOVES: usize = 12;
+
+// How a single solver fared on a board, see `compare_solvers`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverStats {
+    pub name: &'static str,
+    // Length of the solution found, if any
+    pub moves: Option<usize>,
+    // Whether the solution is as short as the one found by A*
+    pub optimal: bool,
+    pub expanded: usize,
+    pub elapsed: Duration,
+}
+
+impl SolverStats {
+    fn measure<F>(name: &'static str, run: F) -> SolverStats
+    where
+        F: FnOnce() -> (Option<Vec<Direction>>, usize),
+    {
+        let start = Instant::now();
+        let (moves, expanded) = run();
+        SolverStats {
+            name,
+            moves: moves.map(|moves| moves.len()),
+            optimal: false,
+            expanded,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+impl fmt::Display for SolverStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let moves = match self.moves {
+            Some(n) => n.to_string(),
+            None => "-".to_string(),
+        };
+        write!(
+            f,
+            "{:<8} {:>4} moves{} {:>9} expanded {:>12?}",
+            self.name,
+            moves,
+            if self.optimal { "*" } else { " " },
+            self.expanded,
+            self.elapsed
+        )
+    }
+}
+
+// Outcome of every solver on the same board
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverComparison {
+    pub astar: SolverStats,
+    pub ida_star: SolverStats,
+    // None if the solution is too long for breadth-first search to finish in reasonable time
+    pub bfs: Option<SolverStats>,
+    pub greedy: SolverStats,
+}
+
+impl SolverComparison {
+    // Stats of every solver that was run
+    pub fn stats(&self) -> Vec<&SolverStats> {
+        let mut stats = vec![&self.astar, &self.ida_star];
+        stats.extend(self.bfs.as_ref());
+        stats.push(&self.greedy);
+        stats
+    }
+}
+
+impl fmt::Display for SolverComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for stats in self.stats() {
+            writeln!(f, "{}", stats)?;
+        }
+        write!(f, "(* optimal)")
+    }
+}
+
+// Solve `b` with A*, IDA*, breadth-first search (for short solutions only) and greedy
+// best-first search, and report how each one did
+pub fn compare_solvers(b: &Board) -> SolverComparison {
+    let mut astar = SolverStats::measure("A*", || Astar::run_with_stats(b));
+    astar.optimal = astar.moves.is_some();
+    let optimal = |mut stats: SolverStats| {
+        stats.optimal = stats.moves.is_some() && stats.moves == astar.moves;
+        stats
+    };
+    let bfs = match astar.moves {
+        Some(n) if n <= BFS_MAX_MOVES => {
+            Some(SolverStats::measure("BFS", || Astar::run_bfs_with_stats(b)))
+        }
+        _ => None,
+    };
+    SolverComparison {
+        ida_star: optimal(SolverStats::measure("IDA*", || IDAStar::run_with_stats(b))),
+        bfs: bfs.map(optimal),
+        greedy: optimal(SolverStats::measure("Greedy", || {
+            Astar::run_greedy_with_stats(b)
+        })),
+        astar,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALMOST_CONFIG: &[u8; 16] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+    const SOLVABLE_CONFIG: &[u8; 16] = &[1, 6, 2, 9, 7, 8, 4, 0, 13, 5, 3, 11, 15, 14, 10, 12];
+
+    #[test]
+    fn optimal_solvers_agree() {
+        let board = Board::new_from(ALMOST_CONFIG).expect("failed to create almost board");
+        let report = compare_solvers(&board);
+        assert_eq!(report.stats().len(), 4);
+        for stats in report.stats().into_iter().filter(|s| s.name != "Greedy") {
+            assert_eq!(stats.moves, Some(1), "{}", stats.name);
+            assert!(stats.optimal);
+        }
+        assert!(report.greedy.moves >= Some(1));
+    }
+
+    #[test]
+    fn skip_bfs_on_long_solutions() {
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let report = compare_solvers(&board);
+        assert_eq!(report.bfs, None);
+        assert_eq!(report.astar.moves, report.ida_star.moves);
+        assert!(report.ida_star.optimal);
+        assert!(report.greedy.moves >= report.astar.moves);
+
+        let report = compare_solvers(&Board::new());
+        assert!(report
+            .stats()
+            .iter()
+            .all(|s| s.moves.is_none() && !s.optimal));
+    }
+}
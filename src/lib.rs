@@ -2,3 +2,19 @@ mod board;
 pub use self::board::*;
 mod solver;
 pub use self::solver::*;
+mod multi;
+pub use self::multi::*;
+mod solution;
+pub use self::solution::*;
+mod batch;
+pub use self::batch::*;
+mod compare;
+pub use self::compare::*;
+mod packed;
+pub use self::packed::*;
+#[cfg(feature = "image")]
+mod render;
+#[cfg(feature = "gif")]
+pub use self::render::*;
+#[cfg(feature = "svg")]
+mod svg;
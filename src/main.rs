@@ -1,17 +1,69 @@
 extern crate clap;
 
-use clap::{App, Arg};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use std::fs;
 use std::io;
 use std::io::Read;
 use std::process;
+use std::time::Duration;
 
 use game15::*;
 
 fn print_game_replay(board: &mut Board, moves: Vec<Direction>) {
     for &dir in moves.iter() {
-        assert!(!board.slide_safe(dir).is_err());
+        let moved = board
+            .slide_safe(dir)
+            .expect("the solver only returns legal moves");
         println!("{}", dir);
-        println!("{}", board);
+        println!("{}", render_move(board, moved));
+    }
+}
+
+fn run_batch(timeout: Option<Duration>) {
+    let mut buffer = String::new();
+    if let Err(err) = io::stdin().read_to_string(&mut buffer) {
+        panic!("IO error: {}", err);
+    }
+    let (outcomes, report) = solve_batch_input(&buffer, timeout);
+    for (i, outcome) in outcomes.into_iter().enumerate() {
+        match outcome {
+            Ok(Ok(moves)) => println!("#{}: {:?}", i + 1, moves),
+            Ok(Err(SolveError::Unsolvable)) => println!("#{}: board cannot be solved", i + 1),
+            Ok(Err(err)) => println!("#{}: could not solve board: {}", i + 1, err),
+            Err(msg) => println!("#{}: invalid board: {}", i + 1, msg),
+        }
+    }
+    println!("{}", report);
+}
+
+fn run_verify(matches: &ArgMatches) {
+    let read = |arg: &str| {
+        let path = matches.value_of(arg).expect("required argument");
+        fs::read_to_string(path).unwrap_or_else(|err| {
+            eprintln!("Cannot read {}: {}", path, err);
+            process::exit(1)
+        })
+    };
+    let board = match read("board").parse::<Board>() {
+        Ok(b) => b,
+        Err(msg) => {
+            eprintln!("Invalid board: {}", msg);
+            process::exit(1)
+        }
+    };
+    let moves = match parse_moves(&read("moves")) {
+        Ok(m) => m,
+        Err(msg) => {
+            eprintln!("Invalid moves: {}", msg);
+            process::exit(1)
+        }
+    };
+    match verify_solution(&board, &moves) {
+        Ok(()) => println!("Valid solution ({} moves)", moves.len()),
+        Err(msg) => {
+            println!("Invalid solution: {}", msg);
+            process::exit(1)
+        }
     }
 }
 
@@ -20,7 +72,10 @@ fn main() {
         .version("0.1.0")
         .author("Rafael Fonseca <r4f4rfs@gmail.com>")
         .about("Solves a 15-puzzle instance")
-        .usage("game15 [--replay] [--random|<stdin>]")
+        .usage(
+            "game15 [--replay] [--random|--batch [--timeout <SECS>]|<stdin>]
+    game15 verify --board <FILE> --moves <FILE>",
+        )
         .after_help(
             "If --random is not supplied, it reads a board configuration from stdin.
 The format expected is one row per line, each row containing 4 space-separated numbers.
@@ -28,7 +83,10 @@ Example:
 0 1 2 3
 4 5 6 7
 8 9 10 11
-12 13 14 15",
+12 13 14 15
+
+With --batch, several boards separated by blank lines are read from stdin. Adding --timeout
+gives up on each board after that many seconds.",
         )
         .arg(
             Arg::with_name("random")
@@ -42,43 +100,73 @@ Example:
                 .takes_value(false)
                 .help("Replays the moves instead of just printing a list"),
         )
+        .arg(
+            Arg::with_name("batch")
+                .long("batch")
+                .takes_value(false)
+                .conflicts_with("random")
+                .help("Solves every board read from stdin and prints a summary"),
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .long("timeout")
+                .takes_value(true)
+                .value_name("SECS")
+                .requires("batch")
+                .help("Time limit for each board in batch mode"),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Checks that a list of moves solves a board")
+                .arg(
+                    Arg::with_name("board")
+                        .long("board")
+                        .takes_value(true)
+                        .required(true)
+                        .help("File with the starting board"),
+                )
+                .arg(
+                    Arg::with_name("moves")
+                        .long("moves")
+                        .takes_value(true)
+                        .required(true)
+                        .help("File with the moves, e.g. [Right, Down, Left]"),
+                ),
+        )
         .get_matches();
 
+    if let Some(matches) = matches.subcommand_matches("verify") {
+        run_verify(matches);
+        return;
+    }
+    if matches.is_present("batch") {
+        let timeout = matches
+            .value_of("timeout")
+            .map(|secs| match parse_timeout(secs) {
+                Ok(timeout) => timeout,
+                Err(msg) => {
+                    eprintln!("Invalid timeout: {}", msg);
+                    process::exit(1)
+                }
+            });
+        run_batch(timeout);
+        return;
+    }
+
     let mut board = if matches.is_present("random") {
         Board::new_random()
     } else {
-        let mut buffer = String::new();
-        match io::stdin().read_to_string(&mut buffer) {
-            Err(err) => panic!("IO error: {}", err),
-            Ok(_) => {
-                let mut config: Vec<u8> = Vec::new();
-                let lines: Vec<&str> = buffer.split('\n').collect();
-                for line in lines {
-                    let mut v: Vec<&str> = line.split(' ').collect();
-                    v.retain(|&x| x != "");
-                    let mut l: Vec<u8> = v
-                        .iter()
-                        .map(|x| x.parse::<u8>().expect("failed to parse number"))
-                        .collect();
-                    config.append(&mut l);
-                }
-                match Board::new_from(config.as_slice()) {
-                    Ok(b) => b,
-                    Err(msg) => {
-                        eprintln!("Invalid board: {}", msg);
-                        process::exit(1)
-                    }
-                }
+        match Board::from_reader(io::stdin().lock()) {
+            Ok(b) => b,
+            Err(msg) => {
+                eprintln!("Invalid board: {}", msg);
+                process::exit(1)
             }
         }
     };
-    println!("{}", board);
-    if !board.solvable() {
-        println!("Board cannot be solved");
-        return;
-    }
-    match Astar::run(&board) {
-        Some(moves) => {
+    println!("{}", board.to_colored_string());
+    match board.solve() {
+        Ok(moves) => {
             println!("Number of moves needed: {}", moves.len());
             if matches.is_present("replay") {
                 print_game_replay(&mut board, moves);
@@ -86,6 +174,7 @@ Example:
                 println!("{:?}", moves);
             }
         }
-        None => println!("Could not solve board"),
+        Err(SolveError::Unsolvable) => println!("Board cannot be solved"),
+        Err(err) => println!("Could not solve board: {}", err),
     }
 }
@@ -0,0 +1,245 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::fmt;
+
+use crate::board::*;
+
+// Variant of the puzzle with several empty tiles (0 appearing more than once). Any tile next to
+// any of the empty tiles may slide. The goal keeps the tiles in increasing order followed by all
+// the empty tiles.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MultiBoard {
+    tiles: [u8; SIZE * SIZE],
+    // Positions of the empty tiles, in increasing order
+    blanks: Vec<usize>,
+}
+
+impl MultiBoard {
+    // Build a board with `k` empty tiles, where the other tiles are numbered 1 to 16 - `k`
+    pub fn new_from(tiles: &[u8]) -> Result<MultiBoard, &'static str> {
+        let mut b = MultiBoard {
+            tiles: [0; SIZE * SIZE],
+            blanks: vec![],
+        };
+        if tiles.len() != b.tiles.len() {
+            return Err("wrong number of tiles");
+        }
+        b.tiles.copy_from_slice(tiles);
+        b.blanks = (0..tiles.len()).filter(|&i| tiles[i] == 0).collect();
+        if b.blanks.is_empty() {
+            return Err("missing empty tile");
+        }
+        let n = (tiles.len() - b.blanks.len()) as u8;
+        let mut seen = [false; SIZE * SIZE];
+        for &t in tiles.iter().filter(|&&t| t != 0) {
+            if t > n {
+                return Err("tiles should be in the range [1, 16 - # of empty tiles]");
+            }
+            if seen[t as usize] {
+                return Err("missing or repeated tiles");
+            }
+            seen[t as usize] = true;
+        }
+        Ok(b)
+    }
+
+    pub fn tiles(&self) -> &[u8; SIZE * SIZE] {
+        &self.tiles
+    }
+
+    pub fn blanks(&self) -> &[usize] {
+        &self.blanks
+    }
+
+    // Position the empty tile at `blank` would move to in direction `dir`, if it is legal
+    fn target(&self, blank: usize, dir: Direction) -> Option<usize> {
+        if self.tiles.get(blank) != Some(&0) {
+            return None;
+        }
+        let (row, col) = (blank / SIZE, blank % SIZE);
+        let pos = match dir {
+            Direction::Left if col > 0 => blank - 1,
+            Direction::Right if col < SIZE - 1 => blank + 1,
+            Direction::Up if row > 0 => blank - SIZE,
+            Direction::Down if row < SIZE - 1 => blank + SIZE,
+            _ => return None,
+        };
+        // Swapping two empty tiles is not a move
+        if self.tiles[pos] == 0 {
+            return None;
+        }
+        Some(pos)
+    }
+
+    // Whether the empty tile at position `blank` can move in direction `dir`
+    pub fn can_slide(&self, blank: usize, dir: Direction) -> bool {
+        self.target(blank, dir).is_some()
+    }
+
+    // Move the empty tile at position `blank` in direction `dir`. Returns the tile that took its
+    // place, like `Board::slide_safe`.
+    pub fn slide_safe(&mut self, blank: usize, dir: Direction) -> Result<u8, &'static str> {
+        let pos = self.target(blank, dir).ok_or("Invalid move")?;
+        self.tiles.swap(blank, pos);
+        self.blanks.retain(|&b| b != blank);
+        self.blanks.push(pos);
+        self.blanks.sort_unstable();
+        Ok(self.tiles[blank])
+    }
+
+    pub fn solved(&self) -> bool {
+        let n = self.tiles.len() - self.blanks.len();
+        self.tiles[..n]
+            .iter()
+            .enumerate()
+            .all(|(i, &t)| t as usize == i + 1)
+    }
+
+    // Sum of the Manhattan distances of the numbered tiles to their goal positions. Each move
+    // shifts a single tile by one, so this never overestimates the moves left.
+    pub fn manhattan_cost(&self) -> usize {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|&(_, &t)| t != 0)
+            .map(|(i, &t)| {
+                let goal = t as usize - 1;
+                let rows = (goal / SIZE).max(i / SIZE) - (goal / SIZE).min(i / SIZE);
+                let cols = (goal % SIZE).max(i % SIZE) - (goal % SIZE).min(i % SIZE);
+                rows + cols
+            })
+            .sum()
+    }
+
+    // Optimal solution found with A* and `manhattan_cost` as the heuristic, as the position of
+    // the empty tile moved and its direction for each move. With several empty tiles every
+    // board can be solved; with a single one the board must pass `Board::solvable`.
+    pub fn solve(&self) -> Option<Vec<(usize, Direction)>> {
+        if self.blanks.len() == 1 {
+            let b = Board::<SIZE>::new_from(&self.tiles).ok()?;
+            if !b.solvable() {
+                return None;
+            }
+        }
+        // Nodes are taken out of `nodes` when expanded, the frontier only keeps their index
+        let mut nodes = vec![Some((self.clone(), Vec::new()))];
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((self.manhattan_cost(), 0)));
+        let mut closed = HashSet::new();
+        while let Some(Reverse((_, id))) = heap.pop() {
+            let (board, moves) = nodes[id].take().expect("node expanded twice");
+            if board.solved() {
+                return Some(moves);
+            }
+            if !closed.insert(board.tiles) {
+                continue;
+            }
+            for &blank in board.blanks() {
+                for &dir in Direction::all().iter() {
+                    let mut next = board.clone();
+                    if next.slide_safe(blank, dir).is_err() || closed.contains(&next.tiles) {
+                        continue;
+                    }
+                    let mut nm = moves.clone();
+                    nm.push((blank, dir));
+                    heap.push(Reverse((nm.len() + next.manhattan_cost(), nodes.len())));
+                    nodes.push(Some((next, nm)));
+                }
+            }
+        }
+        None
+    }
+}
+
+// Same layout as `Board`: right-aligned columns with the empty tiles left blank
+impl fmt::Display for MultiBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = Board::<SIZE>::cell_width();
+        for (i, row) in self.tiles.chunks(SIZE).enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for &t in row {
+                match t {
+                    0 => write!(f, "{:w$}", "", w = width)?,
+                    t => write!(f, "{:>w$}", t, w = width)?,
+                }
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_BLANKS: &[u8; 16] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 0, 14, 0];
+
+    #[test]
+    fn create_multi_board() {
+        let b = MultiBoard::new_from(TWO_BLANKS).expect("failed to create board");
+        assert_eq!(b.blanks(), &[13, 15]);
+        assert!(!b.solved());
+
+        let solved = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 0];
+        let b = MultiBoard::new_from(&solved).expect("failed to create solved board");
+        assert!(b.solved());
+        assert_eq!(b.manhattan_cost(), 0);
+
+        let dup = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 13, 0, 0];
+        assert!(MultiBoard::new_from(&dup).is_err());
+        let range = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 15, 0, 0];
+        assert!(MultiBoard::new_from(&range).is_err());
+        let none = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+        assert!(MultiBoard::new_from(&none).is_err());
+    }
+
+    #[test]
+    fn multi_board_display() {
+        let b = MultiBoard::new_from(TWO_BLANKS).expect("failed to create board");
+        assert_eq!(
+            b.to_string(),
+            "[  1  2  3  4]\n[  5  6  7  8]\n[  9 10 11 12]\n[ 13    14   ]"
+        );
+    }
+
+    #[test]
+    fn multi_board_slide() {
+        let mut b = MultiBoard::new_from(TWO_BLANKS).expect("failed to create board");
+        // Both empty tiles are next to tile 14, but not to each other
+        assert!(b.can_slide(13, Direction::Right));
+        assert!(b.can_slide(15, Direction::Left));
+        assert!(!b.can_slide(15, Direction::Right));
+        assert!(!b.can_slide(14, Direction::Left));
+        assert_eq!(b.manhattan_cost(), 1);
+
+        assert_eq!(b.slide_safe(13, Direction::Right), Ok(14));
+        assert_eq!(b.blanks(), &[14, 15]);
+        assert!(b.solved());
+        // The two empty tiles are now adjacent, which is not a move
+        assert!(b.slide_safe(14, Direction::Right).is_err());
+    }
+
+    #[test]
+    fn multi_board_solve() {
+        let b = MultiBoard::new_from(TWO_BLANKS).expect("failed to create board");
+        assert_eq!(b.solve(), Some(vec![(13, Direction::Right)]));
+
+        let tiles = [1, 2, 3, 4, 5, 0, 6, 8, 9, 10, 7, 11, 13, 14, 12, 0];
+        let mut b = MultiBoard::new_from(&tiles).expect("failed to create board");
+        let moves = b.solve().expect("no solution found");
+        assert_eq!(moves.len(), b.manhattan_cost());
+        for &(blank, dir) in moves.iter() {
+            assert!(b.slide_safe(blank, dir).is_ok());
+        }
+        assert!(b.solved());
+
+        // A single empty tile keeps the parity constraint of the regular puzzle
+        let tiles = [2, 1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+        let b = MultiBoard::new_from(&tiles).expect("failed to create board");
+        assert_eq!(b.solve(), None);
+    }
+}
@@ -0,0 +1,204 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::board::*;
+
+// Board packed in a u64, see `Board::to_u64`
+type State = u64;
+
+// Packed solved board: tile `t` at index `t - 1` and the empty tile last
+const GOAL: State = 0x0fed_cba9_8765_4321;
+
+// Move index of the root node; other nodes index `Direction::all()`
+const NO_MOVE: u8 = 4;
+
+fn tile_at(s: State, idx: usize) -> u8 {
+    ((s >> (4 * idx)) & 0xf) as u8
+}
+
+// Manhattan distance from `idx` to the goal position of `tile`, see `Astar::manhattan`
+fn distance(tile: u8, idx: usize) -> u8 {
+    let goal = tile as usize - 1;
+    let rows = (goal / SIZE).max(idx / SIZE) - (goal / SIZE).min(idx / SIZE);
+    let cols = (goal % SIZE).max(idx % SIZE) - (goal % SIZE).min(idx % SIZE);
+    (rows + cols) as u8
+}
+
+// For every position of the empty tile, where it ends up after each move of `Direction::all()`
+fn move_table() -> [[Option<u8>; 4]; SIZE * SIZE] {
+    let mut table = [[None; 4]; SIZE * SIZE];
+    for (empty, moves) in table.iter_mut().enumerate() {
+        let b = Board::with_blank_at(empty);
+        for (i, &dir) in Direction::all().iter().enumerate() {
+            if b.can_slide(dir) {
+                moves[i] = Some((empty as isize + dir.value()) as u8);
+            }
+        }
+    }
+    table
+}
+
+// A visited board: how it was reached, to rebuild the moves once the goal is found
+struct Node {
+    state: State,
+    empty: u8,
+    spent: u8,
+    heuristic: u8,
+    parent: u32,
+    dir: u8,
+}
+
+// A* specialized for 4x4 boards. Boards are packed into a single u64 and the heuristic is
+// updated incrementally, so nothing is cloned or allocated per node besides the frontier.
+pub struct PackedAstar;
+
+impl PackedAstar {
+    pub fn run(b: &Board) -> Option<Vec<Direction>> {
+        if !b.solvable() {
+            return None;
+        }
+        let table = move_table();
+        let start = b.to_u64();
+        let heuristic: usize = (0..SIZE * SIZE)
+            .map(|i| match tile_at(start, i) {
+                0 => 0,
+                t => distance(t, i) as usize,
+            })
+            .sum();
+        let mut nodes = vec![Node {
+            state: start,
+            empty: b.empty_index() as u8,
+            spent: 0,
+            heuristic: heuristic as u8,
+            parent: 0,
+            dir: NO_MOVE,
+        }];
+        // Lowest estimated cost first, then deepest, as `Solution` orders them
+        let mut heap = BinaryHeap::new();
+        heap.push((Reverse(heuristic as u8), 0u8, 0u32));
+        let mut best: HashMap<State, u8> = HashMap::new();
+        best.insert(start, 0);
+
+        while let Some((_, spent, idx)) = heap.pop() {
+            let node = &nodes[idx as usize];
+            if best.get(&node.state).is_some_and(|&s| s < spent) {
+                continue;
+            }
+            if node.state == GOAL {
+                return Some(PackedAstar::moves(&nodes, idx));
+            }
+            let (state, empty, heuristic, last) =
+                (node.state, node.empty, node.heuristic, node.dir);
+            for (i, to) in table[empty as usize].iter().enumerate() {
+                let to = match to {
+                    // Do not undo last move, opposites are adjacent in `Direction::all()`
+                    Some(to) if last == NO_MOVE || i as u8 != last ^ 1 => *to as usize,
+                    _ => continue,
+                };
+                let tile = tile_at(state, to);
+                let next = (state & !(0xf << (4 * to))) | (tile as State) << (4 * empty);
+                let ns = spent + 1;
+                if best.get(&next).is_some_and(|&s| s <= ns) {
+                    continue;
+                }
+                best.insert(next, ns);
+                let nh = heuristic + distance(tile, empty as usize) - distance(tile, to);
+                nodes.push(Node {
+                    state: next,
+                    empty: to as u8,
+                    spent: ns,
+                    heuristic: nh,
+                    parent: idx,
+                    dir: i as u8,
+                });
+                heap.push((Reverse(ns + nh), ns, nodes.len() as u32 - 1));
+            }
+        }
+        None
+    }
+
+    // Follow the parents of node `idx` back to the root
+    fn moves(nodes: &[Node], mut idx: u32) -> Vec<Direction> {
+        let all = Direction::all();
+        let mut moves = Vec::with_capacity(nodes[idx as usize].spent as usize);
+        while nodes[idx as usize].dir != NO_MOVE {
+            moves.push(all[nodes[idx as usize].dir as usize]);
+            idx = nodes[idx as usize].parent;
+        }
+        moves.reverse();
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Astar;
+    use rand::thread_rng;
+    use std::time::Instant;
+
+    const SOLVED_CONFIG: &[u8; SIZE * SIZE] =
+        &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    const SOLVABLE_CONFIG: &[u8; SIZE * SIZE] =
+        &[1, 6, 2, 9, 7, 8, 4, 0, 13, 5, 3, 11, 15, 14, 10, 12];
+
+    fn scrambled(steps: usize) -> Board {
+        let board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        match board.random_walk(steps, &mut thread_rng()).pop() {
+            Some((mut b, dir)) => {
+                b.slide(dir);
+                b
+            }
+            None => board,
+        }
+    }
+
+    #[test]
+    fn packing() {
+        let board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(board.to_u64(), GOAL);
+        assert_eq!(PackedAstar::run(&board), Some(vec![]));
+    }
+
+    #[test]
+    fn packed_matches_astar() {
+        let mut boards = vec![Board::new_from(SOLVABLE_CONFIG).expect("failed to create board")];
+        boards.extend((0..10).map(|i| scrambled(10 + 3 * i)));
+        for board in boards {
+            let moves = PackedAstar::run(&board).expect("no solution found");
+            let reference = Astar::run(&board).expect("no reference solution found");
+            assert_eq!(moves.len(), reference.len());
+
+            let mut b = board.clone();
+            for dir in moves {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert!(b.solved());
+        }
+    }
+
+    #[test]
+    fn packed_unsolvable() {
+        let mut tiles = *SOLVED_CONFIG;
+        tiles.swap(0, 1);
+        let board = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(PackedAstar::run(&board), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn packed_benchmark() {
+        let boards: Vec<Board> = (0..20).map(|_| scrambled(40)).collect();
+        let start = Instant::now();
+        for board in boards.iter() {
+            Astar::run(board).expect("no reference solution found");
+        }
+        let reference = start.elapsed();
+        let start = Instant::now();
+        for board in boards.iter() {
+            PackedAstar::run(board).expect("no solution found");
+        }
+        let packed = start.elapsed();
+        println!("Astar: {:?}, PackedAstar: {:?}", reference, packed);
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(feature = "gif")]
+use image::{codecs::gif::GifEncoder, codecs::gif::Repeat, Delay, DynamicImage, Frame};
+use image::{Rgb, RgbImage};
+#[cfg(feature = "gif")]
+use std::{fs::File, io, path::Path};
+
+use crate::board::*;
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const TILE: Rgb<u8> = Rgb([222, 184, 135]);
+const INK: Rgb<u8> = Rgb([0, 0, 0]);
+
+// 3x5 bitmap font for the digits 0-9, one row per entry with the leftmost pixel as bit 2
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+impl Board {
+    // Render the board as a grid of `cell_px` wide square tiles, leaving the empty tile blank
+    pub fn to_image(&self, cell_px: u32) -> RgbImage {
+        let side = SIZE as u32 * cell_px;
+        let mut img = RgbImage::from_pixel(side, side, BACKGROUND);
+        for (i, &t) in self.tiles().iter().enumerate() {
+            if t == 0 {
+                continue;
+            }
+            let x0 = (i % SIZE) as u32 * cell_px;
+            let y0 = (i / SIZE) as u32 * cell_px;
+            for y in y0..y0 + cell_px {
+                for x in x0..x0 + cell_px {
+                    let border =
+                        x == x0 || y == y0 || x == x0 + cell_px - 1 || y == y0 + cell_px - 1;
+                    img.put_pixel(x, y, if border { INK } else { TILE });
+                }
+            }
+            draw_number(&mut img, t, x0, y0, cell_px);
+        }
+        img
+    }
+}
+
+// Write to `path` an animated GIF of `moves` played from `start`, showing each board for
+// `frame_ms` milliseconds
+#[cfg(feature = "gif")]
+pub fn export_replay_gif(
+    start: &Board,
+    moves: &[Direction],
+    path: &Path,
+    frame_ms: u16,
+) -> io::Result<()> {
+    const CELL_PX: u32 = 32;
+    let delay = Delay::from_numer_denom_ms(frame_ms as u32, 1);
+    let frame = |b: &Board| {
+        let img = DynamicImage::ImageRgb8(b.to_image(CELL_PX)).into_rgba8();
+        Frame::from_parts(img, 0, 0, delay)
+    };
+    let mut board = start.clone();
+    let mut frames = vec![frame(&board)];
+    for &dir in moves {
+        board
+            .slide_safe(dir)
+            .map_err(|msg| io::Error::new(io::ErrorKind::InvalidInput, msg))?;
+        frames.push(frame(&board));
+    }
+    let mut encoder = GifEncoder::new(File::create(path)?);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .and_then(|_| encoder.encode_frames(frames))
+        .map_err(io::Error::other)
+}
+
+// Draw `n` centered in the cell whose top-left corner is at (`x0`, `y0`)
+fn draw_number(img: &mut RgbImage, n: u8, x0: u32, y0: u32, cell_px: u32) {
+    let digits: Vec<usize> = n.to_string().bytes().map(|b| (b - b'0') as usize).collect();
+    // Each digit is 3 pixels wide plus 1 pixel of spacing, scaled to about half the cell
+    let width = digits.len() as u32 * 4 - 1;
+    let scale = (cell_px / 2 / width).max(1);
+    if width * scale > cell_px || 5 * scale > cell_px {
+        return;
+    }
+    let left = x0 + (cell_px - width * scale) / 2;
+    let top = y0 + (cell_px - 5 * scale) / 2;
+    for (k, &d) in digits.iter().enumerate() {
+        for (row, bits) in DIGITS[d].iter().enumerate() {
+            for col in 0..3u32 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                let px = left + (k as u32 * 4 + col) * scale;
+                let py = top + row as u32 * scale;
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        img.put_pixel(px + dx, py + dy, INK);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_dimensions() {
+        let b = Board::new();
+        let img = b.to_image(32);
+        assert_eq!(img.dimensions(), (SIZE as u32 * 32, SIZE as u32 * 32));
+        // The empty tile is left blank
+        assert_eq!(*img.get_pixel(16, 16), BACKGROUND);
+        assert_ne!(*img.get_pixel(48, 16), BACKGROUND);
+    }
+
+    #[test]
+    #[cfg(feature = "gif")]
+    fn replay_gif() {
+        use image::codecs::gif::GifDecoder;
+        use image::AnimationDecoder;
+
+        let b = Board::new_from(&[1, 2, 3, 4, 5, 0, 6, 8, 9, 10, 7, 11, 13, 14, 15, 12])
+            .expect("failed to create easy board");
+        let moves = [
+            Direction::Right,
+            Direction::Down,
+            Direction::Right,
+            Direction::Down,
+        ];
+        let path = std::env::temp_dir().join(format!("game15-replay-{}.gif", std::process::id()));
+        export_replay_gif(&b, &moves, &path, 100).expect("failed to export replay");
+
+        let file = File::open(&path).expect("failed to open replay");
+        assert!(file.metadata().expect("no metadata").len() > 0);
+        let decoder = GifDecoder::new(io::BufReader::new(file)).expect("invalid GIF");
+        let frames = decoder
+            .into_frames()
+            .collect_frames()
+            .expect("invalid frames");
+        assert_eq!(frames.len(), moves.len() + 1);
+        std::fs::remove_file(&path).expect("failed to remove replay");
+
+        assert!(export_replay_gif(&b, &[Direction::Up, Direction::Up], &path, 100).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
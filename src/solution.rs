@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+
+use crate::board::*;
+use crate::solver::{Astar, SolveError};
+
+// Number of times consecutive moves switch between the horizontal and vertical axis. Lower
+// values mean smoother panning when animating the solution.
+pub fn solution_smoothness(moves: &[Direction]) -> usize {
+    moves
+        .windows(2)
+        .filter(|win| win[0].horizontal() != win[1].horizontal())
+        .count()
+}
+
+// Overall character of a solution, see `analyze_solution`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SolutionShape {
+    pub left: usize,
+    pub right: usize,
+    pub up: usize,
+    pub down: usize,
+    // Length of the longest sequence of identical consecutive moves
+    pub longest_run: usize,
+    // Same as `solution_smoothness`
+    pub axis_switches: usize,
+}
+
+// Count the moves in each direction, the longest run and the axis switches of `moves`
+pub fn analyze_solution(moves: &[Direction]) -> SolutionShape {
+    let mut shape = SolutionShape {
+        longest_run: rle_encode(moves).iter().map(|&(_, n)| n).max().unwrap_or(0),
+        axis_switches: solution_smoothness(moves),
+        ..Default::default()
+    };
+    for dir in moves {
+        match dir {
+            Direction::Left => shape.left += 1,
+            Direction::Right => shape.right += 1,
+            Direction::Up => shape.up += 1,
+            Direction::Down => shape.down += 1,
+        }
+    }
+    shape
+}
+
+// Remap `moves` so they apply to the board rotated clockwise by `rotation`
+pub fn rotate_moves(moves: &[Direction], rotation: Rotation) -> Vec<Direction> {
+    moves.iter().map(|dir| dir.rotated(rotation)).collect()
+}
+
+// Run-length encode `moves`, collapsing consecutive identical moves into (move, count) pairs
+pub fn rle_encode(moves: &[Direction]) -> Vec<(Direction, usize)> {
+    let mut runs: Vec<(Direction, usize)> = Vec::new();
+    for &dir in moves {
+        match runs.last_mut() {
+            Some((last, count)) if *last == dir => *count += 1,
+            _ => runs.push((dir, 1)),
+        }
+    }
+    runs
+}
+
+pub fn rle_decode(runs: &[(Direction, usize)]) -> Vec<Direction> {
+    runs.iter()
+        .flat_map(|&(dir, count)| std::iter::repeat_n(dir, count))
+        .collect()
+}
+
+// Parse a move log: directions separated by whitespace or commas. The `Debug` output of a
+// move list, e.g. `[Right, Down]`, is accepted as well.
+pub fn parse_moves(s: &str) -> Result<Vec<Direction>, String> {
+    s.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|tok| !tok.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+// Check that `moves` are all legal from `start` and leave the board solved
+pub fn verify_solution(start: &Board, moves: &[Direction]) -> Result<(), String> {
+    let mut board = start.clone();
+    for (i, &dir) in moves.iter().enumerate() {
+        if board.slide_safe(dir).is_err() {
+            return Err(format!("move #{} ({}) is illegal", i + 1, dir));
+        }
+    }
+    if !board.solved() {
+        return Err("moves do not solve the board".to_string());
+    }
+    Ok(())
+}
+
+// Quick check that `moves` were recorded for `start`, without playing them all: the first move
+// must be legal, and since every move changes the Manhattan heuristic by exactly one, there must
+// be at least as many moves as the heuristic with the same parity. Use `verify_solution` for a
+// full check.
+pub fn solution_belongs_to(start: &Board, moves: &[Direction]) -> bool {
+    if moves.first().is_some_and(|&dir| !start.can_slide(dir)) {
+        return false;
+    }
+    let bound = start.manhattan_lower_bound();
+    moves.len() >= bound && (moves.len() - bound) % 2 == 0
+}
+
+// Whether playing `moves` from `start` ever comes back to a board seen before, a sign of wasted
+// moves. An illegal move leaves the board unchanged, so it counts as a repetition too.
+pub fn has_repeated_state(start: &Board, moves: &[Direction]) -> bool {
+    let mut board = start.clone();
+    let mut seen = HashSet::new();
+    seen.insert(board.to_u64());
+    for &dir in moves {
+        if board.slide_safe(dir).is_err() || !seen.insert(board.to_u64()) {
+            return true;
+        }
+    }
+    false
+}
+
+// Whether `b` and its mirror image (see `Board::transposed`) have optimal solutions of the same
+// length, and whether the transposed solution of one solves the other. The solutions themselves
+// may differ move by move: the solver tries directions in a fixed order, which the mirror image
+// does not preserve, so ties between equally good moves can be broken differently.
+// Reflecting horizontally or vertically is not checked since it moves the empty tile's goal.
+pub fn solutions_are_mirror_consistent(b: &Board) -> bool {
+    let mirror = b.transposed();
+    match (b.solve(), mirror.solve()) {
+        (Ok(moves), Ok(mirrored)) => {
+            let transposed: Vec<Direction> = moves.iter().map(|dir| dir.transposed()).collect();
+            moves.len() == mirrored.len() && verify_solution(&mirror, &transposed).is_ok()
+        }
+        (Err(err), Err(mirror_err)) => err == mirror_err,
+        _ => false,
+    }
+}
+
+// Every board reached while playing `moves` from `start`, starting with `start` itself
+pub fn trace(start: &Board, moves: &[Direction]) -> Result<Vec<Board>, String> {
+    let mut board = start.clone();
+    let mut boards = Vec::with_capacity(moves.len() + 1);
+    boards.push(board.clone());
+    for (i, &dir) in moves.iter().enumerate() {
+        if board.slide_safe(dir).is_err() {
+            return Err(format!("move #{} ({}) is illegal", i + 1, dir));
+        }
+        boards.push(board.clone());
+    }
+    Ok(boards)
+}
+
+// Solve `b` and return every board from `b` to the solved board, e.g. to animate the solution
+pub fn solve_and_collect_frames(b: &Board) -> Result<Vec<Board>, SolveError> {
+    let moves = b.solve()?;
+    Ok(trace(b, &moves).expect("the solver only returns legal moves"))
+}
+
+// Manhattan heuristic of `start` and of the board after each of `moves`, e.g. to chart how
+// close the board gets to solved. Stops at the first illegal move.
+pub fn heuristic_trend(start: &Board, moves: &[Direction]) -> Vec<usize> {
+    let mut board = start.clone();
+    let mut trend = Vec::with_capacity(moves.len() + 1);
+    trend.push(Astar::manhattan(&board));
+    for &dir in moves {
+        if board.slide_safe(dir).is_err() {
+            break;
+        }
+        trend.push(Astar::manhattan(&board));
+    }
+    trend
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASY_CONFIG: &[u8; 16] = &[1, 2, 3, 4, 5, 0, 6, 8, 9, 10, 7, 11, 13, 14, 15, 12];
+
+    #[test]
+    fn smoothness() {
+        use Direction::*;
+        assert_eq!(solution_smoothness(&[]), 0);
+        assert_eq!(solution_smoothness(&[Up]), 0);
+        assert_eq!(solution_smoothness(&[Left, Right, Left]), 0);
+        assert_eq!(
+            solution_smoothness(&[Up, Up, Left, Left, Down, Right, Right]),
+            3
+        );
+    }
+
+    #[test]
+    fn shape() {
+        use Direction::*;
+        assert_eq!(analyze_solution(&[]), SolutionShape::default());
+        assert_eq!(
+            analyze_solution(&[Up, Up, Left, Left, Left, Down, Right, Right]),
+            SolutionShape {
+                left: 3,
+                right: 2,
+                up: 2,
+                down: 1,
+                longest_run: 3,
+                axis_switches: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn rotate_full_turn() {
+        use Direction::*;
+        let moves = vec![Right, Down, Right, Down, Left, Up];
+        let mut rotated = moves.clone();
+        for _ in 0..4 {
+            rotated = rotate_moves(&rotated, Rotation::Deg90);
+        }
+        assert_eq!(rotated, moves);
+        assert_eq!(
+            rotate_moves(&rotate_moves(&moves, Rotation::Deg90), Rotation::Deg270),
+            moves
+        );
+        assert_eq!(
+            rotate_moves(&moves, Rotation::Deg180),
+            vec![Left, Up, Left, Up, Right, Down]
+        );
+    }
+
+    #[test]
+    fn rle_round_trip() {
+        use Direction::*;
+        assert!(rle_encode(&[]).is_empty());
+        assert!(rle_decode(&[]).is_empty());
+
+        let moves = [Right, Right, Right, Down, Left, Left, Up, Right];
+        let runs = rle_encode(&moves);
+        assert_eq!(
+            runs,
+            vec![(Right, 3), (Down, 1), (Left, 2), (Up, 1), (Right, 1)]
+        );
+        assert_eq!(rle_decode(&runs), moves.to_vec());
+    }
+
+    #[test]
+    fn parse_move_log() {
+        use Direction::*;
+        assert_eq!(parse_moves(""), Ok(vec![]));
+        assert_eq!(parse_moves("[]"), Ok(vec![]));
+        assert_eq!(
+            parse_moves("[Right, Down, Left, Up]\n"),
+            Ok(vec![Right, Down, Left, Up])
+        );
+        assert_eq!(parse_moves("r d\nl\tu"), Ok(vec![Right, Down, Left, Up]));
+        assert!(parse_moves("Right, Diagonal").is_err());
+    }
+
+    #[test]
+    fn repeated_state() {
+        use Direction::*;
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert!(!has_repeated_state(&board, &[]));
+        assert!(!has_repeated_state(&board, &[Right, Down, Right, Down]));
+        assert!(has_repeated_state(
+            &board,
+            &[Right, Left, Right, Down, Right, Down]
+        ));
+        // Cycling the empty tile three times around a 2x2 square restores the three tiles
+        let mut cycle = vec![];
+        for _ in 0..3 {
+            cycle.extend_from_slice(&[Left, Down, Right, Up]);
+        }
+        assert!(has_repeated_state(&board, &cycle));
+        assert!(!has_repeated_state(&board, &cycle[..11]));
+        assert!(has_repeated_state(&board, &[Up, Up, Up]));
+    }
+
+    #[test]
+    fn mirror_consistent() {
+        let boards = [
+            EASY_CONFIG,
+            &[1, 6, 2, 9, 7, 8, 4, 0, 13, 5, 3, 11, 15, 14, 10, 12],
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 14, 12, 13, 11, 10, 15],
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        ];
+        for tiles in boards.iter() {
+            let board = Board::new_from(*tiles).expect("failed to create board");
+            assert!(solutions_are_mirror_consistent(&board), "{}", board);
+        }
+    }
+
+    #[test]
+    fn trace_moves() {
+        use Direction::*;
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let boards = trace(&board, &[Right, Down]).expect("failed to trace moves");
+        assert_eq!(boards.len(), 3);
+        assert_eq!(boards[0], board);
+        assert_eq!(boards[2].empty_index(), board.empty_index() + 5);
+        assert!(trace(&board, &[Up, Up, Up]).is_err());
+    }
+
+    #[test]
+    fn collect_frames() {
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let frames = solve_and_collect_frames(&board).expect("no solution found");
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames.first(), Some(&board));
+        assert!(frames.last().is_some_and(|b| b.solved()));
+
+        let board = Board::new();
+        assert_eq!(
+            solve_and_collect_frames(&board),
+            Err(SolveError::Unsolvable)
+        );
+    }
+
+    #[test]
+    fn trend() {
+        let board = Board::new_from(&[1, 6, 2, 9, 7, 8, 4, 0, 13, 5, 3, 11, 15, 14, 10, 12])
+            .expect("failed to create board");
+        let moves = board.solve().expect("no solution found");
+        let trend = heuristic_trend(&board, &moves);
+        assert_eq!(trend.len(), moves.len() + 1);
+        assert_eq!(trend.first(), Some(&Astar::manhattan(&board)));
+        assert_eq!(trend.last(), Some(&0));
+        // Every move changes the distance of exactly one tile by one
+        assert!(trend
+            .windows(2)
+            .all(|w| w[0].max(w[1]) - w[0].min(w[1]) == 1));
+
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(heuristic_trend(&board, &[Direction::Up; 3]).len(), 2);
+    }
+
+    #[test]
+    fn belongs_to() {
+        use Direction::*;
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let other = Board::new_from(&[1, 6, 2, 9, 7, 8, 4, 0, 13, 5, 3, 11, 15, 14, 10, 12])
+            .expect("failed to create board");
+        let moves = other.solve().expect("no solution found");
+        assert!(solution_belongs_to(&board, &[Right, Down, Right, Down]));
+        assert!(solution_belongs_to(&other, &moves));
+        // Illegal first move
+        assert!(!solution_belongs_to(&other, &[Right, Down, Right, Down]));
+        // Too short, then wrong parity
+        assert!(!solution_belongs_to(&board, &[Right, Down]));
+        assert!(!solution_belongs_to(&board, &moves[..5]));
+        assert!(solution_belongs_to(&Board::goal(), &[]));
+    }
+
+    #[test]
+    fn verify() {
+        use Direction::*;
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert!(verify_solution(&board, &[Right, Down, Right, Down]).is_ok());
+        assert!(verify_solution(&board, &[Right, Down, Right]).is_err());
+        assert!(verify_solution(&board, &[Up, Up, Up]).is_err());
+    }
+}
@@ -1,127 +1,2295 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::time::{Duration, Instant};
 
 use crate::board;
 use board::*;
 
+// Number of boards `Astar::run_avoiding` expands before giving up when it cannot tell upfront
+// whether the board can be solved
+const AVOIDING_MAX_EXPANDED: usize = 1 << 20;
+
+// A node of the search frontier: a board along with the moves that led to it
 #[derive(Clone, Eq, PartialEq)]
-struct Solution {
+pub struct Solution<const N: usize = SIZE> {
     cost: usize,
+    // Cost of the moves made so far, which is their number unless moves have different costs
+    spent: usize,
     moves: Vec<Direction>,
+    board: Board<N>,
+}
+
+impl<const N: usize> Solution<N> {
+    // Estimated total number of moves (moves made so far plus the heuristic)
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+
+    pub fn moves(&self) -> &[Direction] {
+        &self.moves
+    }
+
+    pub fn board(&self) -> &Board<N> {
+        &self.board
+    }
+
+    // Estimated number of bytes this entry takes while stored in the search frontier
+    fn size(&self) -> usize {
+        mem::size_of::<Solution<N>>()
+            + self.moves.capacity() * mem::size_of::<Direction>()
+            + self.board.tiles().len()
+    }
+}
+
+// Estimated number of bytes a board takes while stored in a closed set
+fn closed_size<const N: usize>() -> usize {
+    mem::size_of::<Board<N>>() + N * N
+}
+
+impl<const N: usize> Ord for Solution<N> {
+    fn cmp(&self, other: &Solution<N>) -> Ordering {
+        other
+            .cost
+            .cmp(&self.cost)
+            .then_with(|| self.moves.len().cmp(&other.moves.len()))
+    }
+}
+
+impl<const N: usize> PartialOrd for Solution<N> {
+    fn partial_cmp(&self, other: &Solution<N>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Reasons a solver can fail to return a solution
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SolveError {
+    // The board is not in the solved board's parity class
+    Unsolvable,
+    // The search ran out of boards to expand without reaching the goal, e.g. because the moves
+    // were restricted
+    Exhausted,
+    // The solver ran out of time
+    Timeout,
+    // The solver expanded more nodes than allowed
+    BudgetExceeded,
+    // The search frontier grew past the allowed memory
+    MemoryLimit,
+    // The caller asked the solver to stop
+    Cancelled,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Unsolvable => write!(f, "board cannot be solved"),
+            SolveError::Exhausted => write!(f, "search exhausted"),
+            SolveError::Timeout => write!(f, "time limit exceeded"),
+            SolveError::BudgetExceeded => write!(f, "node budget exceeded"),
+            SolveError::MemoryLimit => write!(f, "memory limit exceeded"),
+            SolveError::Cancelled => write!(f, "search cancelled"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+// Describes the search space explored by `Astar::search`
+trait Problem<const N: usize = SIZE> {
+    // Estimated number of moves left to solve `b`
+    fn heuristic(&self, b: &Board<N>) -> usize;
+
+    // Whether the empty tile of `b` may be moved in direction `dir`
+    fn allowed(&self, _b: &Board<N>, _dir: Direction) -> bool {
+        true
+    }
+
+    // Cost of moving the empty tile of `b` in direction `dir`
+    fn step_cost(&self, _b: &Board<N>, _dir: Direction) -> usize {
+        1
+    }
+
+    // Whether the search is over once `b` is reached
+    fn is_goal(&self, b: &Board<N>) -> bool {
+        b.solved()
+    }
+
+    // Frontier priority of `b` reached after spending `spent`: lower values are expanded first
+    fn priority(&self, b: &Board<N>, spent: usize) -> usize {
+        self.heuristic(b) + spent
+    }
+}
+
+impl<F, const N: usize> Problem<N> for F
+where
+    F: Fn(&Board<N>) -> usize,
+{
+    fn heuristic(&self, b: &Board<N>) -> usize {
+        self(b)
+    }
+}
+
+// Manhattan-guided search where the empty tile may never step on the `forbidden` positions
+struct Avoiding<'a> {
+    forbidden: &'a [usize],
+}
+
+impl<'a> Problem for Avoiding<'a> {
+    fn heuristic(&self, b: &Board) -> usize {
+        Astar::manhattan(b)
+    }
+
+    fn allowed(&self, b: &Board, dir: Direction) -> bool {
+        let pos = (b.empty_index() as isize + dir.value()) as usize;
+        !self.forbidden.contains(&pos)
+    }
+}
+
+// Binary max-heap of frontier nodes ordered by a comparator that is passed in rather than stored,
+// so that it is kept once in the `Search` instead of in every entry
+struct Frontier<const N: usize = SIZE> {
+    nodes: Vec<Solution<N>>,
+}
+
+impl<const N: usize> Frontier<N> {
+    fn with_capacity(capacity: usize) -> Frontier<N> {
+        Frontier {
+            nodes: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn peek(&self) -> Option<&Solution<N>> {
+        self.nodes.first()
+    }
+
+    fn push<C>(&mut self, node: Solution<N>, cmp: &C)
+    where
+        C: Fn(&Solution<N>, &Solution<N>) -> Ordering,
+    {
+        self.nodes.push(node);
+        let mut i = self.nodes.len() - 1;
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if cmp(&self.nodes[i], &self.nodes[parent]) != Ordering::Greater {
+                break;
+            }
+            self.nodes.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn pop<C>(&mut self, cmp: &C) -> Option<Solution<N>>
+    where
+        C: Fn(&Solution<N>, &Solution<N>) -> Ordering,
+    {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let top = self.nodes.swap_remove(0);
+        let len = self.nodes.len();
+        let mut i = 0;
+        loop {
+            let mut largest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < len && cmp(&self.nodes[child], &self.nodes[largest]) == Ordering::Greater
+                {
+                    largest = child;
+                }
+            }
+            if largest == i {
+                break;
+            }
+            self.nodes.swap(i, largest);
+            i = largest;
+        }
+        Some(top)
+    }
+}
+
+// Search ranking boards with an arbitrary priority function of the board and the number of moves
+// made to reach it
+struct Priority<F> {
+    priority: F,
+}
+
+impl<F> Problem for Priority<F>
+where
+    F: Fn(&Board, usize) -> usize,
+{
+    fn heuristic(&self, b: &Board) -> usize {
+        (self.priority)(b, 0)
+    }
+
+    fn priority(&self, b: &Board, spent: usize) -> usize {
+        (self.priority)(b, spent)
+    }
+}
+
+// Search that is over as soon as the tiles in `pattern` reach their goal positions, the other
+// tiles being don't-cares
+struct Partial<'a> {
+    pattern: &'a [u8],
+}
+
+impl<'a> Problem for Partial<'a> {
+    fn heuristic(&self, b: &Board) -> usize {
+        b.tiles()
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| self.pattern.contains(t))
+            .map(|(i, &t)| Astar::manhattan_dist(t, i, SIZE))
+            .sum()
+    }
+
+    fn is_goal(&self, b: &Board) -> bool {
+        self.heuristic(b) == 0
+    }
+}
+
+// Search where tiles fall for free: sliding a tile down (the empty tile going up) costs nothing
+// while sliding a tile up costs 2 moves
+struct Gravity;
+
+impl Gravity {
+    fn tile_cost(tile: u8, idx: usize) -> usize {
+        if tile == 0 {
+            return 0;
+        }
+        let goal = tile as usize - 1;
+        let (row, goal_row) = (idx / SIZE, goal / SIZE);
+        let cols = (goal % SIZE).max(idx % SIZE) - (goal % SIZE).min(idx % SIZE);
+        // Only climbing rows costs anything
+        cols + 2 * row.saturating_sub(goal_row)
+    }
+}
+
+impl Problem for Gravity {
+    fn heuristic(&self, b: &Board) -> usize {
+        b.tiles()
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| Gravity::tile_cost(t, i))
+            .sum()
+    }
+
+    fn step_cost(&self, _b: &Board, dir: Direction) -> usize {
+        match dir {
+            Direction::Up => 0,
+            Direction::Down => 2,
+            Direction::Left | Direction::Right => 1,
+        }
+    }
+}
+
+// Search for a custom goal board, guided by the Manhattan distance to the tiles' positions in it
+struct Goal {
+    goal: Board,
+    // Index of every tile in the goal board
+    positions: [usize; SIZE * SIZE],
+}
+
+impl Goal {
+    fn new(goal: &Board) -> Goal {
+        let mut positions = [0; SIZE * SIZE];
+        for (i, &t) in goal.tiles().iter().enumerate() {
+            positions[t as usize] = i;
+        }
+        Goal {
+            goal: goal.clone(),
+            positions,
+        }
+    }
+}
+
+impl Problem for Goal {
+    fn heuristic(&self, b: &Board) -> usize {
+        b.tiles()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &t)| t != 0)
+            .map(|(i, &t)| {
+                let goal = self.positions[t as usize];
+                let rows = (goal / SIZE).max(i / SIZE) - (goal / SIZE).min(i / SIZE);
+                let cols = (goal % SIZE).max(i % SIZE) - (goal % SIZE).min(i % SIZE);
+                rows + cols
+            })
+            .sum()
+    }
+
+    fn is_goal(&self, b: &Board) -> bool {
+        b.solved_to(&self.goal)
+    }
+}
+
+// Manhattan-guided search where moving a tile out of the solved prefix (see
+// `Board::solved_prefix`) costs one extra move
+struct Stable;
+
+impl Problem for Stable {
+    fn heuristic(&self, b: &Board) -> usize {
+        Astar::manhattan(b)
+    }
+
+    fn step_cost(&self, b: &Board, dir: Direction) -> usize {
+        let moved = (b.empty_index() as isize + dir.value()) as usize;
+        if moved < b.solved_prefix() {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+// Resource bounds and tuning knobs of a single search
+#[derive(Default)]
+struct Options {
+    // Give up with `SolveError::MemoryLimit` once the frontier and the closed set are estimated
+    // to take more than this many bytes
+    max_bytes: Option<usize>,
+    // Number of frontier entries to reserve up front
+    capacity: usize,
+    // Nodes whose estimated cost exceeds this are never pushed to the frontier
+    max_cost: Option<usize>,
+    // Give up with `SolveError::BudgetExceeded` after expanding this many boards
+    max_expanded: Option<usize>,
+    // Expand a board again each time it is reached through another path instead of keeping a
+    // closed set, to find every optimal solution
+    revisit: bool,
+}
+
+// Outcome of a single step of the search
+enum Step<const N: usize = SIZE> {
+    // A node was popped and its successors pushed to the frontier
+    Expanded(Solution<N>),
+    // A solved board was popped
+    Solved(Solution<N>),
+    // The frontier is empty
+    Exhausted,
+}
+
+// State of an A* search over the moves allowed by `problem`, expanding first the greatest
+// frontier node according to `cmp`
+struct Search<P, C, const N: usize = SIZE> {
+    problem: P,
+    opts: Options,
+    cmp: C,
+    heap: Frontier<N>,
+    // Boards already expanded, see `Options::revisit`
+    closed: HashSet<Board<N>>,
+    // Estimated number of bytes taken by the frontier, see `used_bytes` for the whole search
+    used: usize,
+    // Number of boards expanded so far
+    expanded: usize,
+}
+
+impl<P, C, const N: usize> Search<P, C, N>
+where
+    P: Problem<N>,
+    C: Fn(&Solution<N>, &Solution<N>) -> Ordering,
+{
+    fn new(b: &Board<N>, problem: P, opts: Options, cmp: C) -> Search<P, C, N> {
+        let root = Solution {
+            cost: problem.priority(b, 0),
+            spent: 0,
+            moves: vec![],
+            board: b.clone(),
+        };
+        let mut heap = Frontier::with_capacity(opts.capacity);
+        let mut used = 0;
+        if opts.max_cost.is_none_or(|max| root.cost <= max) {
+            used = root.size();
+            heap.push(root, &cmp);
+        }
+        Search {
+            problem,
+            opts,
+            cmp,
+            heap,
+            closed: HashSet::new(),
+            used,
+            expanded: 0,
+        }
+    }
+
+    // Pop the most promising node and, unless it is solved, push its successors
+    fn step(&mut self) -> Result<Step<N>, SolveError> {
+        let node = loop {
+            let node = match self.heap.pop(&self.cmp) {
+                Some(node) => node,
+                None => return Ok(Step::Exhausted),
+            };
+            self.used -= node.size();
+            // Skip boards already expanded when reached through another path
+            if self.opts.revisit || !self.closed.contains(&node.board) {
+                break node;
+            }
+        };
+        if self.problem.is_goal(&node.board) {
+            return Ok(Step::Solved(node));
+        }
+        self.expanded += 1;
+        if let Some(max) = self.opts.max_expanded {
+            if self.expanded > max {
+                return Err(SolveError::BudgetExceeded);
+            }
+        }
+        if !self.opts.revisit {
+            self.closed.insert(node.board.clone());
+            self.check_memory()?;
+        }
+        for &dir in Direction::all().iter() {
+            // Do not undo last move
+            if let Some(last) = node.moves.last() {
+                if last.opposites(dir) {
+                    continue;
+                }
+            }
+            if !node.board.can_slide(dir) || !self.problem.allowed(&node.board, dir) {
+                continue;
+            }
+            let mut b = node.board.clone();
+            b.slide(dir);
+            if !self.opts.revisit && self.closed.contains(&b) {
+                continue;
+            }
+            let ns = node.spent + self.problem.step_cost(&node.board, dir);
+            let nc = self.problem.priority(&b, ns);
+            if self.opts.max_cost.is_some_and(|max| nc > max) {
+                continue;
+            }
+            let mut nm = node.moves.clone();
+            nm.push(dir);
+            let next = Solution {
+                cost: nc,
+                spent: ns,
+                moves: nm,
+                board: b,
+            };
+            self.used += next.size();
+            self.check_memory()?;
+            self.heap.push(next, &self.cmp);
+        }
+        Ok(Step::Expanded(node))
+    }
+
+    // Estimated number of bytes taken by the frontier and the closed set
+    fn used_bytes(&self) -> usize {
+        self.used + self.closed.len() * closed_size::<N>()
+    }
+
+    fn check_memory(&self) -> Result<(), SolveError> {
+        match self.opts.max_bytes {
+            Some(max) if self.used_bytes() > max => Err(SolveError::MemoryLimit),
+            _ => Ok(()),
+        }
+    }
+
+    // Step until every optimal solution is found, assuming the default frontier order, and
+    // return them. Stops early once `max` solutions have been collected.
+    fn optimal(&mut self, max: Option<usize>) -> Result<Vec<Solution<N>>, SolveError> {
+        let mut found: Vec<Solution<N>> = Vec::new();
+        loop {
+            if max.is_some_and(|max| found.len() >= max) {
+                break;
+            }
+            // Once a solution is known, only nodes of the same cost can lead to another one
+            if let Some(best) = found.first() {
+                match self.heap.peek() {
+                    Some(top) if top.cost <= best.cost => {}
+                    _ => break,
+                }
+            }
+            match self.step()? {
+                Step::Expanded(_) => {}
+                Step::Solved(node) => found.push(node),
+                Step::Exhausted => break,
+            }
+        }
+        Ok(found)
+    }
+
+    // Step until a solution is found
+    fn finish(&mut self) -> Result<Vec<Direction>, SolveError> {
+        self.finish_checked(|| Ok(()))
+    }
+
+    // Step until a solution is found, calling `check` before each step to decide whether to
+    // give up early
+    fn finish_checked<F>(&mut self, mut check: F) -> Result<Vec<Direction>, SolveError>
+    where
+        F: FnMut() -> Result<(), SolveError>,
+    {
+        loop {
+            check()?;
+            match self.step()? {
+                Step::Expanded(_) => {}
+                Step::Solved(node) => return Ok(node.moves),
+                Step::Exhausted => return Err(SolveError::Exhausted),
+            }
+        }
+    }
+}
+
+// A board popped from the frontier during the search, see `Astar::search_iter`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchEvent {
+    pub board: Board,
+    // Estimated total number of moves of the board
+    pub cost: usize,
+    // Number of moves made to reach the board
+    pub depth: usize,
+}
+
+impl From<&Solution> for SearchEvent {
+    fn from(node: &Solution) -> SearchEvent {
+        SearchEvent {
+            board: node.board.clone(),
+            cost: node.cost,
+            depth: node.moves.len(),
+        }
+    }
+}
+
+struct SearchIter<P, C> {
+    search: Search<P, C>,
+    done: bool,
+}
+
+impl<P, C> Iterator for SearchIter<P, C>
+where
+    P: Problem,
+    C: Fn(&Solution, &Solution) -> Ordering + Copy,
+{
+    type Item = SearchEvent;
+
+    fn next(&mut self) -> Option<SearchEvent> {
+        if self.done {
+            return None;
+        }
+        match self.search.step() {
+            Ok(Step::Expanded(node)) => Some(SearchEvent::from(&node)),
+            Ok(Step::Solved(node)) => {
+                self.done = true;
+                Some(SearchEvent::from(&node))
+            }
+            Ok(Step::Exhausted) | Err(_) => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+// Outcome of `StepSolver::step`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum StepResult {
+    // A board was expanded; holds its estimated total number of moves
+    Progress(usize),
+    Solved(Vec<Direction>),
+    Exhausted,
+}
+
+// Plain Manhattan-guided search with the default frontier order
+type ManhattanSearch = Search<fn(&Board) -> usize, fn(&Solution, &Solution) -> Ordering>;
+
+// A* search that can be run one node at a time, e.g. to single-step it while debugging
+pub struct StepSolver {
+    search: ManhattanSearch,
+    solution: Option<Vec<Direction>>,
+}
+
+impl StepSolver {
+    pub fn new(b: &Board) -> StepSolver {
+        StepSolver {
+            search: Search::new(b, Astar::manhattan, Options::default(), Solution::cmp),
+            solution: None,
+        }
+    }
+
+    // Pop one node and expand it. Once solved, keeps returning the same solution.
+    pub fn step(&mut self) -> StepResult {
+        if let Some(ref moves) = self.solution {
+            return StepResult::Solved(moves.clone());
+        }
+        match self.search.step() {
+            Ok(Step::Expanded(node)) => StepResult::Progress(node.cost),
+            Ok(Step::Solved(node)) => {
+                self.solution = Some(node.moves.clone());
+                StepResult::Solved(node.moves)
+            }
+            Ok(Step::Exhausted) | Err(_) => StepResult::Exhausted,
+        }
+    }
+}
+
+// Data structure holding the search frontier, see `Astar::run_backend`
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Backend {
+    // Binary heap ordered by `Solution::cmp`
+    #[default]
+    Heap,
+    // Frontier nodes grouped by cost in an ordered map, popping the most recent node of the
+    // lowest cost first
+    Buckets,
+}
+
+pub struct Astar;
+
+impl Astar {
+    // Calculate the Manhattan distance of tile `tile` at index `idx` to its goal position. The
+    // empty tile is not counted so the estimate never exceeds the real number of moves.
+    pub(crate) fn manhattan_dist(tile: u8, idx: usize, width: usize) -> usize {
+        if tile == 0 {
+            return 0;
+        }
+        let goal = tile as usize - 1;
+        let rows = (goal / width).max(idx / width) - (goal / width).min(idx / width);
+        let cols = (goal % width).max(idx % width) - (goal % width).min(idx % width);
+        rows + cols
+    }
+
+    // Sum of the Manhattan distances of every tile in `b`
+    pub fn manhattan<const N: usize>(b: &Board<N>) -> usize {
+        b.tiles()
+            .iter()
+            .enumerate()
+            .map(|(i, t)| Astar::manhattan_dist(*t, i, N))
+            .sum()
+    }
+
+    // Manhattan distance plus two moves for every tile that has to leave its line (row or
+    // column) to let another tile of the same line get past it
+    pub fn linear_conflict(b: &Board) -> usize {
+        let tiles = b.tiles();
+        let mut extra = 0;
+        for line in 0..SIZE {
+            // Goal columns of the tiles already in their goal row, from left to right
+            let row: Vec<usize> = (0..SIZE)
+                .map(|col| tiles[line * SIZE + col])
+                .filter(|&t| t != 0 && (t as usize - 1) / SIZE == line)
+                .map(|t| (t as usize - 1) % SIZE)
+                .collect();
+            // Goal rows of the tiles already in their goal column, from top to bottom
+            let col: Vec<usize> = (0..SIZE)
+                .map(|row| tiles[row * SIZE + line])
+                .filter(|&t| t != 0 && (t as usize - 1) % SIZE == line)
+                .map(|t| (t as usize - 1) / SIZE)
+                .collect();
+            extra += 2 * Astar::conflicts(&row) + 2 * Astar::conflicts(&col);
+        }
+        Astar::manhattan(b) + extra
+    }
+
+    // Minimum number of tiles to take out of a line so the remaining `goals` are in order,
+    // i.e. the length of the line minus its longest increasing subsequence
+    fn conflicts(goals: &[usize]) -> usize {
+        let mut lis = vec![1; goals.len()];
+        for i in 0..goals.len() {
+            for j in 0..i {
+                if goals[j] < goals[i] {
+                    lis[i] = lis[i].max(lis[j] + 1);
+                }
+            }
+        }
+        goals.len() - lis.iter().max().unwrap_or(&0)
+    }
+
+    // A* search over the moves allowed by `problem`, guided by its heuristic
+    fn search<P, const N: usize>(
+        b: &Board<N>,
+        problem: P,
+        opts: Options,
+    ) -> Result<Vec<Direction>, SolveError>
+    where
+        P: Problem<N>,
+    {
+        Astar::search_by(b, problem, opts, Solution::cmp)
+    }
+
+    // Same as `search`, but the frontier expands first the greatest node according to `cmp`
+    fn search_by<P, C, const N: usize>(
+        b: &Board<N>,
+        problem: P,
+        opts: Options,
+        cmp: C,
+    ) -> Result<Vec<Direction>, SolveError>
+    where
+        P: Problem<N>,
+        C: Fn(&Solution<N>, &Solution<N>) -> Ordering + Copy,
+    {
+        Search::new(b, problem, opts, cmp).finish()
+    }
+
+    // Iterate over the boards popped from the frontier while solving `b`, in order, ending with
+    // the solved board. Useful to animate how the search progresses.
+    pub fn search_iter(b: &Board) -> impl Iterator<Item = SearchEvent> {
+        SearchIter {
+            search: Search::new(b, Astar::manhattan, Options::default(), Solution::cmp),
+            done: false,
+        }
+    }
+
+    // Search for a solution of `b` expanding at most `max_nodes` boards, and return the board
+    // closest to solved (by Manhattan distance) seen along the way with the moves reaching it.
+    // That is the solved board if the search finished within the budget.
+    pub fn best_effort_progress(b: &Board, max_nodes: usize) -> (Vec<Direction>, Board) {
+        let mut search = Search::new(b, Astar::manhattan, Options::default(), Solution::cmp);
+        let mut best = (Astar::manhattan(b), vec![], b.clone());
+        for _ in 0..max_nodes {
+            let node = match search.step() {
+                Ok(Step::Expanded(node)) => node,
+                Ok(Step::Solved(node)) => return (node.moves, node.board),
+                Ok(Step::Exhausted) | Err(_) => break,
+            };
+            let h = Astar::manhattan(&node.board);
+            if h < best.0 {
+                best = (h, node.moves, node.board);
+            }
+        }
+        (best.1, best.2)
+    }
+
+    // Solve `b` and count the nodes expanded at each depth: `profile[d]` is the number of
+    // boards reached in `d` moves whose successors were pushed to the frontier
+    pub fn run_depth_profile(b: &Board) -> (Option<Vec<Direction>>, Vec<usize>) {
+        let mut search = Search::new(b, Astar::manhattan, Options::default(), Solution::cmp);
+        let mut profile = Vec::new();
+        loop {
+            match search.step() {
+                Ok(Step::Expanded(node)) => {
+                    let depth = node.moves.len();
+                    if profile.len() <= depth {
+                        profile.resize(depth + 1, 0);
+                    }
+                    profile[depth] += 1;
+                }
+                Ok(Step::Solved(node)) => return (Some(node.moves), profile),
+                Ok(Step::Exhausted) | Err(_) => return (None, profile),
+            }
+        }
+    }
+
+    // Same as `run`, also returning the number of boards expanded
+    pub fn run_with_stats(b: &Board) -> (Option<Vec<Direction>>, usize) {
+        if !b.solvable() {
+            return (None, 0);
+        }
+        let search = Search::new(b, Astar::manhattan, Options::default(), Solution::cmp);
+        Astar::count_expanded(search)
+    }
+
+    // Greedy best-first search: expand first the board closest to solved, ignoring the moves
+    // made to reach it, and never revisit a board. Usually expands far fewer boards than `run`,
+    // but the solution is rarely optimal. Also returns the number of boards expanded.
+    pub fn run_greedy_with_stats(b: &Board) -> (Option<Vec<Direction>>, usize) {
+        if !b.solvable() {
+            return (None, 0);
+        }
+        let mut seen = HashSet::new();
+        seen.insert(b.to_u64());
+        let mut nodes = vec![(b.clone(), vec![])];
+        // Lowest heuristic first, then oldest node
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((Astar::manhattan(b), 0)));
+        let mut expanded = 0;
+        while let Some(Reverse((_, idx))) = heap.pop() {
+            let (board, moves): (Board, Vec<Direction>) = nodes[idx].clone();
+            if board.solved() {
+                return (Some(moves), expanded);
+            }
+            expanded += 1;
+            for &dir in Direction::all().iter() {
+                if !board.can_slide(dir) {
+                    continue;
+                }
+                let mut next = board.clone();
+                next.slide(dir);
+                if !seen.insert(next.to_u64()) {
+                    continue;
+                }
+                let mut next_moves = moves.clone();
+                next_moves.push(dir);
+                heap.push(Reverse((Astar::manhattan(&next), nodes.len())));
+                nodes.push((next, next_moves));
+            }
+        }
+        (None, expanded)
+    }
+
+    // Breadth-first search, expanding boards in the order they were reached, and the number of
+    // boards expanded. Optimal, but the frontier grows exponentially with the solution length.
+    pub fn run_bfs_with_stats(b: &Board) -> (Option<Vec<Direction>>, usize) {
+        if !b.solvable() {
+            return (None, 0);
+        }
+        let bfs = |a: &Solution, b: &Solution| b.moves.len().cmp(&a.moves.len());
+        let search = Search::new(b, Astar::manhattan, Options::default(), bfs);
+        Astar::count_expanded(search)
+    }
+
+    fn count_expanded<P, C>(mut search: Search<P, C>) -> (Option<Vec<Direction>>, usize)
+    where
+        P: Problem,
+        C: Fn(&Solution, &Solution) -> Ordering + Copy,
+    {
+        let mut expanded = 0;
+        loop {
+            match search.step() {
+                Ok(Step::Expanded(_)) => expanded += 1,
+                Ok(Step::Solved(node)) => return (Some(node.moves), expanded),
+                Ok(Step::Exhausted) | Err(_) => return (None, expanded),
+            }
+        }
+    }
+
+    // Solve `b`, failing right away with `SolveError::Unsolvable` instead of running a search
+    // that can never succeed when `b` is not solvable
+    pub fn solve<const N: usize>(b: &Board<N>) -> Result<Vec<Direction>, SolveError> {
+        if !b.solvable() {
+            return Err(SolveError::Unsolvable);
+        }
+        Astar::run(b).ok_or(SolveError::Exhausted)
+    }
+
+    pub fn run<const N: usize>(b: &Board<N>) -> Option<Vec<Direction>> {
+        // The frontier grows roughly exponentially with the distance to the goal
+        let cap = 1 << Astar::manhattan(b).min(16);
+        Astar::run_with_capacity(b, cap)
+    }
+
+    // Solve `b` reserving room for `cap` frontier entries up front. This is a performance
+    // tuning knob that avoids reallocations on hard boards; it does not change the result.
+    pub fn run_with_capacity<const N: usize>(b: &Board<N>, cap: usize) -> Option<Vec<Direction>> {
+        let opts = Options {
+            capacity: cap,
+            ..Default::default()
+        };
+        Astar::search(b, Astar::manhattan, opts).ok()
+    }
+
+    // Solve `b` keeping the frontier in `backend`. Both return optimal solutions, possibly
+    // different ones of the same length.
+    pub fn run_backend(b: &Board, backend: Backend) -> Option<Vec<Direction>> {
+        match backend {
+            Backend::Heap => Astar::run(b),
+            Backend::Buckets => Astar::run_buckets(b, None).ok(),
+        }
+    }
+
+    // Same as `run_backend` but giving up with `SolveError::MemoryLimit` once the frontier and
+    // the closed set are estimated to take more than `max_bytes`, see `run_mem_limited`
+    pub fn run_backend_mem_limited(
+        b: &Board,
+        backend: Backend,
+        max_bytes: usize,
+    ) -> Result<Vec<Direction>, SolveError> {
+        match backend {
+            Backend::Heap => Astar::run_mem_limited(b, max_bytes),
+            Backend::Buckets => Astar::run_buckets(b, Some(max_bytes)),
+        }
+    }
+
+    fn run_buckets(b: &Board, max_bytes: Option<usize>) -> Result<Vec<Direction>, SolveError> {
+        if !b.solvable() {
+            return Err(SolveError::Unsolvable);
+        }
+        let mut buckets: BTreeMap<usize, Vec<Solution>> = BTreeMap::new();
+        let root = Solution {
+            cost: Astar::manhattan(b),
+            spent: 0,
+            moves: vec![],
+            board: b.clone(),
+        };
+        // Estimated number of bytes taken by the frontier and the closed set, as in `Search`
+        let mut used = root.size();
+        let check_memory = |used: usize| match max_bytes {
+            Some(max) if used > max => Err(SolveError::MemoryLimit),
+            _ => Ok(()),
+        };
+        check_memory(used)?;
+        buckets.insert(root.cost, vec![root]);
+        // Boards already expanded, as in `Search`
+        let mut closed = HashSet::new();
+        while let Some(mut bucket) = buckets.first_entry() {
+            let node = bucket.get_mut().pop().expect("empty buckets are removed");
+            if bucket.get().is_empty() {
+                bucket.remove();
+            }
+            used -= node.size();
+            if node.board.solved() {
+                return Ok(node.moves);
+            }
+            if !closed.insert(node.board.clone()) {
+                continue;
+            }
+            used += closed_size::<SIZE>();
+            check_memory(used)?;
+            for &dir in Direction::all().iter() {
+                // Do not undo last move
+                if node.moves.last().is_some_and(|last| last.opposites(dir)) {
+                    continue;
+                }
+                if !node.board.can_slide(dir) {
+                    continue;
+                }
+                let mut board = node.board.clone();
+                board.slide(dir);
+                if closed.contains(&board) {
+                    continue;
+                }
+                let mut moves = node.moves.clone();
+                moves.push(dir);
+                let next = Solution {
+                    cost: node.spent + 1 + Astar::manhattan(&board),
+                    spent: node.spent + 1,
+                    moves,
+                    board,
+                };
+                used += next.size();
+                check_memory(used)?;
+                buckets.entry(next.cost).or_default().push(next);
+            }
+        }
+        Err(SolveError::Exhausted)
+    }
+
+    // Solve `b` expanding first the greatest frontier node according to `cmp` (the default
+    // order, `Solution::cmp`, ranks lower costs higher). Meant to stress the solver: depending
+    // on `cmp` the result may not be optimal and the search may not even terminate.
+    pub fn run_with_comparator<C>(b: &Board, cmp: C) -> Option<Vec<Direction>>
+    where
+        C: Fn(&Solution, &Solution) -> Ordering,
+    {
+        Astar::search_by(b, Astar::manhattan, Options::default(), &cmp).ok()
+    }
+
+    // Solve `b` until done or until `cancel` is set, e.g. from another thread, in which case it
+    // returns `SolveError::Cancelled`
+    pub fn run_cancellable(b: &Board, cancel: &AtomicBool) -> Result<Vec<Direction>, SolveError> {
+        let mut search = Search::new(b, Astar::manhattan, Options::default(), Solution::cmp);
+        search.finish_checked(|| {
+            if cancel.load(AtomicOrdering::Relaxed) {
+                Err(SolveError::Cancelled)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    // Solve `b`, giving up with `SolveError::Timeout` once `timeout` has elapsed
+    pub fn run_with_timeout(b: &Board, timeout: Duration) -> Result<Vec<Direction>, SolveError> {
+        if !b.solvable() {
+            return Err(SolveError::Unsolvable);
+        }
+        let start = Instant::now();
+        let mut search = Search::new(b, Astar::manhattan, Options::default(), Solution::cmp);
+        search.finish_checked(|| {
+            if start.elapsed() > timeout {
+                Err(SolveError::Timeout)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    // Solve `b` knowing it takes at most `upper` moves, e.g. the length of a greedy solution,
+    // pruning every board that cannot be solved within that bound. Returns `None` if there is no
+    // solution of at most `upper` moves, which also makes it terminate on unsolvable boards.
+    pub fn run_with_bound(b: &Board, upper: usize) -> Option<Vec<Direction>> {
+        let opts = Options {
+            max_cost: Some(upper),
+            ..Default::default()
+        };
+        Astar::search(b, Astar::manhattan, opts).ok()
+    }
+
+    // Solve `b` while keeping the estimated size of the frontier and the closed set under
+    // `max_bytes`, giving up with `SolveError::MemoryLimit` instead of exhausting the available
+    // memory.
+    pub fn run_mem_limited(b: &Board, max_bytes: usize) -> Result<Vec<Direction>, SolveError> {
+        let opts = Options {
+            max_bytes: Some(max_bytes),
+            ..Default::default()
+        };
+        Astar::search(b, Astar::manhattan, opts)
+    }
+
+    // Solve `b` expanding first the boards with the lowest `priority(board, moves)`, where
+    // `moves` is the number of moves made to reach `board`. `run` is equivalent to a priority
+    // of `moves + Astar::manhattan(board)`; the solution is optimal as long as the priority is
+    // `moves` plus an admissible heuristic.
+    pub fn run_with_priority<F>(b: &Board, priority: F) -> Option<Vec<Direction>>
+    where
+        F: Fn(&Board, usize) -> usize,
+    {
+        Astar::search(b, Priority { priority }, Options::default()).ok()
+    }
+
+    // Solve `b` with each tile's Manhattan distance multiplied by `weights[tile]`. This is a
+    // research knob: any weight greater than 1 makes the heuristic inadmissible, so the returned
+    // solution is no longer guaranteed to be optimal. Returns None if there is not a weight for
+    // every tile.
+    pub fn run_weighted_tiles(b: &Board, weights: &[usize]) -> Option<Vec<Direction>> {
+        if weights.len() < b.tiles().len() {
+            return None;
+        }
+        Astar::run_with_priority(b, |board, moves| {
+            let heuristic: usize = board
+                .tiles()
+                .iter()
+                .enumerate()
+                .map(|(i, &t)| weights[t as usize] * Astar::manhattan_dist(t, i, SIZE))
+                .sum();
+            moves + heuristic
+        })
+    }
+
+    // Solve `b` without ever moving the empty tile onto one of the `forbidden` positions.
+    // Returns None when there is no such solution: the empty tile must be able to reach its goal
+    // position, the tiles it cannot get to must already be in place, and the board must pass
+    // the usual parity check. By Wilson's theorem that is enough when the cells left to the
+    // empty tile are 2-connected and not a simple cycle; otherwise some arrangements cannot be
+    // reached and the search gives up after expanding `AVOIDING_MAX_EXPANDED` boards.
+    pub fn run_avoiding(b: &Board, forbidden: &[usize]) -> Option<Vec<Direction>> {
+        let region = Astar::blank_region(b.empty_index(), forbidden);
+        let goal: Board = Board::goal();
+        let in_place = |i: usize| region[i] || b.tiles()[i] == goal.tiles()[i];
+        if !region[goal.empty_index()] || !(0..region.len()).all(in_place) || !b.solvable() {
+            return None;
+        }
+        let max_expanded = if Astar::wilson_region(&region) {
+            None
+        } else {
+            Some(AVOIDING_MAX_EXPANDED)
+        };
+        let opts = Options {
+            max_expanded,
+            ..Default::default()
+        };
+        Astar::search(b, Avoiding { forbidden }, opts).ok()
+    }
+
+    // Positions the empty tile can travel to from `from` without crossing `forbidden`
+    fn blank_region(from: usize, forbidden: &[usize]) -> Vec<bool> {
+        let mut seen = vec![false; SIZE * SIZE];
+        if forbidden.contains(&from) {
+            return seen;
+        }
+        seen[from] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(from);
+        while let Some(pos) = queue.pop_front() {
+            for n in Board::<SIZE>::neighbours(pos) {
+                if !forbidden.contains(&n) && !seen[n] {
+                    seen[n] = true;
+                    queue.push_back(n);
+                }
+            }
+        }
+        seen
+    }
+
+    // Whether the positions in `region` are 2-connected and do not form a simple cycle, in which
+    // case every arrangement passing the parity check can be reached
+    fn wilson_region(region: &[bool]) -> bool {
+        let cells: Vec<usize> = (0..region.len()).filter(|&i| region[i]).collect();
+        if cells.len() < 3 {
+            return false;
+        }
+        let degree = |pos: usize| {
+            Board::<SIZE>::neighbours(pos)
+                .into_iter()
+                .filter(|&n| region[n])
+                .count()
+        };
+        if cells.iter().all(|&pos| degree(pos) == 2) {
+            return false;
+        }
+        // 2-connected: taking out any single cell leaves the others connected
+        cells.iter().all(|&cut| {
+            let mut forbidden: Vec<usize> = (0..region.len()).filter(|&i| !region[i]).collect();
+            forbidden.push(cut);
+            let start = cells
+                .iter()
+                .find(|&&pos| pos != cut)
+                .copied()
+                .unwrap_or(cut);
+            let reached = Astar::blank_region(start, &forbidden);
+            reached.iter().filter(|&&r| r).count() == cells.len() - 1
+        })
+    }
+
+    // Every distinct optimal solution of `b`. Their number can grow very quickly with the
+    // solution length.
+    pub fn all_optimal(b: &Board) -> Vec<Vec<Direction>> {
+        Astar::optimal_solutions(b, None)
+    }
+
+    // Same as `all_optimal`, but stop once `max` distinct optimal solutions are found
+    pub fn optimal_solutions_limited(b: &Board, max: usize) -> Vec<Vec<Direction>> {
+        Astar::optimal_solutions(b, Some(max))
+    }
+
+    fn optimal_solutions(b: &Board, max: Option<usize>) -> Vec<Vec<Direction>> {
+        let opts = Options {
+            revisit: true,
+            ..Default::default()
+        };
+        let mut search = Search::new(b, Astar::manhattan, opts, Solution::cmp);
+        match search.optimal(max) {
+            Ok(found) => found.into_iter().map(|node| node.moves).collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    // Optimal solution of `b` together with another optimal solution starting with a different
+    // move, if there is one
+    pub fn run_with_alternative(b: &Board) -> Option<(Vec<Direction>, Option<Vec<Direction>>)> {
+        let mut found = Astar::all_optimal(b).into_iter();
+        let best = found.next()?;
+        let alternative = found.find(|moves| moves.first() != best.first());
+        Some((best, alternative))
+    }
+
+    // Same as `run_partial`, but among the optimal solutions pick one leaving the empty tile as
+    // close as possible to position `blank`, e.g. a corner for nicer animations
+    pub fn run_partial_preferring(
+        b: &Board,
+        pattern: &[u8],
+        blank: usize,
+    ) -> Option<Vec<Direction>> {
+        let distance = |pos: usize| {
+            let rows = (pos / SIZE).max(blank / SIZE) - (pos / SIZE).min(blank / SIZE);
+            let cols = (pos % SIZE).max(blank % SIZE) - (pos % SIZE).min(blank % SIZE);
+            rows + cols
+        };
+        let mut search = Search::new(b, Partial { pattern }, Options::default(), Solution::cmp);
+        let found = search.optimal(None).ok()?;
+        found
+            .into_iter()
+            .min_by_key(|node| distance(node.board.empty_index()))
+            .map(|node| node.moves)
+    }
+
+    // Find the shortest sequence of moves that brings the tiles in `pattern` to their goal
+    // positions, whatever happens to the other tiles. This is the building block of solving a
+    // board in stages, e.g. the top row first.
+    pub fn run_partial(b: &Board, pattern: &[u8]) -> Option<Vec<Direction>> {
+        Astar::search(b, Partial { pattern }, Options::default()).ok()
+    }
+
+    // Shortest sequence of moves taking `b` to `goal` instead of the usual solved board. Only
+    // boards in the same parity class as `goal` can reach it.
+    pub fn run_to(b: &Board, goal: &Board) -> Option<Vec<Direction>> {
+        if b.parity_class() != goal.parity_class() {
+            return None;
+        }
+        Astar::search(b, Goal::new(goal), Options::default()).ok()
+    }
+
+    // Solve `b` avoiding to break up the tiles already in place at the start of the board, so
+    // the solved region tends to grow steadily like when solving by hand. The solution may be a
+    // few moves longer than the optimal one.
+    pub fn run_stable(b: &Board) -> Option<Vec<Direction>> {
+        Astar::search(b, Stable, Options::default()).ok()
+    }
+
+    // Solve `b` in the "gravity" variant where sliding a tile down is free and sliding it up
+    // costs 2. The returned solution minimizes that total cost, not the number of moves.
+    pub fn run_gravity(b: &Board) -> Option<Vec<Direction>> {
+        Astar::search(b, Gravity, Options::default()).ok()
+    }
+
+    // Solve `b` and express the solution backwards: the returned moves take the solved board to
+    // `b` instead of `b` to the solved board.
+    pub fn run_reverse(b: &Board) -> Option<Vec<Direction>> {
+        Astar::run(b).map(|moves| moves.iter().rev().map(|dir| dir.opposite()).collect())
+    }
+}
+
+// Depth-limited depth-first search. Solutions are not optimal, but memory usage is only
+// proportional to `max_depth`, which is enough for quick hints on nearly solved boards.
+pub struct Dfs;
+
+impl Dfs {
+    pub fn run(b: &Board, max_depth: usize) -> Option<Vec<Direction>> {
+        let mut board = b.clone();
+        let mut moves = Vec::with_capacity(max_depth);
+        if Dfs::visit(&mut board, &mut moves, max_depth) {
+            Some(moves)
+        } else {
+            None
+        }
+    }
+
+    // Extend `moves` from `board` until it is solved, backtracking on failure
+    fn visit(board: &mut Board, moves: &mut Vec<Direction>, max_depth: usize) -> bool {
+        if board.solved() {
+            return true;
+        }
+        if moves.len() == max_depth {
+            return false;
+        }
+        for &dir in Direction::all().iter() {
+            // Do not undo last move
+            if let Some(last) = moves.last() {
+                if last.opposites(dir) {
+                    continue;
+                }
+            }
+            if !board.can_slide(dir) {
+                continue;
+            }
+            board.slide(dir);
+            moves.push(dir);
+            if Dfs::visit(board, moves, max_depth) {
+                return true;
+            }
+            moves.pop();
+            board.slide(dir.opposite());
+        }
+        false
+    }
+}
+
+// Iterative deepening A*: depth-first searches bounded by a threshold on the estimated total
+// number of moves, raised each time to the lowest estimate that went past it. Slower than
+// `Astar`, but memory usage is only proportional to the solution length.
+pub struct IDAStar;
+
+impl IDAStar {
+    pub fn run(b: &Board) -> Option<Vec<Direction>> {
+        IDAStar::run_with_threshold_callback(b, |_| {})
+    }
+
+    // Same as `run`, calling `cb` with the threshold of each iteration as a coarse progress
+    // indicator. The last threshold is the length of the solution.
+    pub fn run_with_threshold_callback<F>(b: &Board, cb: F) -> Option<Vec<Direction>>
+    where
+        F: FnMut(usize),
+    {
+        IDAStar::search(b, cb, &mut 0)
+    }
+
+    // Same as `run`, also returning the number of boards expanded over all iterations
+    pub fn run_with_stats(b: &Board) -> (Option<Vec<Direction>>, usize) {
+        let mut expanded = 0;
+        let moves = IDAStar::search(b, |_| {}, &mut expanded);
+        (moves, expanded)
+    }
+
+    fn search<F>(b: &Board, mut cb: F, expanded: &mut usize) -> Option<Vec<Direction>>
+    where
+        F: FnMut(usize),
+    {
+        if !b.solvable() {
+            return None;
+        }
+        let mut board = b.clone();
+        let mut moves = Vec::new();
+        let heuristic = Astar::manhattan(b);
+        let mut threshold = heuristic;
+        loop {
+            cb(threshold);
+            match IDAStar::visit(&mut board, &mut moves, heuristic, threshold, expanded) {
+                Ok(()) => return Some(moves),
+                Err(usize::MAX) => return None,
+                Err(next) => threshold = next,
+            }
+        }
+    }
+
+    // Look for a solution within `threshold`, otherwise return the lowest estimate past it.
+    // `heuristic` is the Manhattan distance of `board`, updated as tiles move.
+    fn visit(
+        board: &mut Board,
+        moves: &mut Vec<Direction>,
+        heuristic: usize,
+        threshold: usize,
+        expanded: &mut usize,
+    ) -> Result<(), usize> {
+        let estimate = moves.len() + heuristic;
+        if estimate > threshold {
+            return Err(estimate);
+        }
+        if heuristic == 0 {
+            return Ok(());
+        }
+        *expanded += 1;
+        let mut next = usize::MAX;
+        for &dir in Direction::all().iter() {
+            // Do not undo last move
+            if moves.last().is_some_and(|last| last.opposites(dir)) || !board.can_slide(dir) {
+                continue;
+            }
+            let empty = board.empty_index();
+            let pos = (empty as isize + dir.value()) as usize;
+            let tile = board.tiles()[pos];
+            let h = heuristic + Astar::manhattan_dist(tile, empty, SIZE)
+                - Astar::manhattan_dist(tile, pos, SIZE);
+            board.slide(dir);
+            moves.push(dir);
+            match IDAStar::visit(board, moves, h, threshold, expanded) {
+                Ok(()) => return Ok(()),
+                Err(estimate) => next = next.min(estimate),
+            }
+            moves.pop();
+            board.slide(dir.opposite());
+        }
+        Err(next)
+    }
+}
+
+// A board kept in memory by `SmaStar`
+struct SmaNode {
     board: Board,
+    spent: usize,
+    // Estimated total number of moves, backed up from the successors once they are generated
+    cost: usize,
+    parent: Option<usize>,
+    dir: Option<Direction>,
+    // Successors never generated
+    pending: Vec<Direction>,
+    // Successors dropped to free memory, along with their cost at the time
+    forgotten: Vec<(Direction, usize)>,
+    children: Vec<usize>,
 }
 
-impl Ord for Solution {
-    fn cmp(&self, other: &Solution) -> Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.moves.len().cmp(&other.moves.len()))
+// Position in the frontier: lowest cost first, then deepest, then most recent
+type SmaKey = (usize, Reverse<usize>, Reverse<usize>);
+
+struct SmaSearch {
+    nodes: Vec<Option<SmaNode>>,
+    free: Vec<usize>,
+    // Nodes with successors that are not in memory
+    open: BTreeSet<SmaKey>,
+    live: usize,
+}
+
+impl SmaSearch {
+    fn node(&self, id: usize) -> &SmaNode {
+        self.nodes[id].as_ref().expect("dropped node")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut SmaNode {
+        self.nodes[id].as_mut().expect("dropped node")
+    }
+
+    fn key(&self, id: usize) -> SmaKey {
+        let node = self.node(id);
+        (node.cost, Reverse(node.spent), Reverse(id))
+    }
+
+    fn add(&mut self, node: SmaNode) -> usize {
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        self.live += 1;
+        self.open.insert(self.key(id));
+        id
+    }
+
+    fn set_cost(&mut self, id: usize, cost: usize) {
+        let reopen = self.open.remove(&self.key(id));
+        self.node_mut(id).cost = cost;
+        if reopen {
+            self.open.insert(self.key(id));
+        }
+    }
+
+    // Drop the shallowest of the leaves with the highest cost, other than `keep`, remembering
+    // its cost in its parent. Returns false if there is no such leaf.
+    fn drop_worst(&mut self, keep: usize) -> bool {
+        let worst = self
+            .open
+            .iter()
+            .rev()
+            .map(|&(_, _, Reverse(id))| id)
+            .find(|&id| {
+                let node = self.node(id);
+                id != keep && node.parent.is_some() && node.children.is_empty()
+            });
+        let id = match worst {
+            Some(id) => id,
+            None => return false,
+        };
+        self.open.remove(&self.key(id));
+        let node = self.nodes[id].take().expect("dropped node");
+        self.free.push(id);
+        self.live -= 1;
+        let parent = node.parent.expect("the root is never dropped");
+        let key = self.key(parent);
+        let p = self.node_mut(parent);
+        p.children.retain(|&c| c != id);
+        p.forgotten
+            .push((node.dir.expect("only the root has no move"), node.cost));
+        self.open.insert(key);
+        true
+    }
+
+    // Once every successor of `id` was generated, its cost is the lowest cost among them. Update
+    // it and its ancestors accordingly.
+    fn backup(&mut self, mut id: usize) {
+        loop {
+            let node = self.node(id);
+            if !node.pending.is_empty() {
+                return;
+            }
+            let children = node.children.iter().map(|&c| self.node(c).cost);
+            let forgotten = node.forgotten.iter().map(|&(_, cost)| cost);
+            let cost = children.chain(forgotten).min().unwrap_or(usize::MAX);
+            if cost == node.cost {
+                return;
+            }
+            let parent = node.parent;
+            self.set_cost(id, cost);
+            match parent {
+                Some(p) => id = p,
+                None => return,
+            }
+        }
+    }
+
+    fn moves(&self, mut id: usize) -> Vec<Direction> {
+        let mut moves = Vec::with_capacity(self.node(id).spent);
+        while let Some(dir) = self.node(id).dir {
+            moves.push(dir);
+            id = self.node(id).parent.expect("only the root has no move");
+        }
+        moves.reverse();
+        moves
+    }
+}
+
+// Simplified Memory-bounded A*: an A* search that never keeps more than `max_nodes` boards in
+// memory. When full, it drops the least promising leaf and backs up its cost to its parent so
+// the subtree can be regenerated later if needed. The solution is optimal if the optimal path
+// fits in memory, otherwise it is the best one found within the budget.
+pub struct SmaStar;
+
+impl SmaStar {
+    pub fn run(b: &Board, max_nodes: usize) -> Option<Vec<Direction>> {
+        if max_nodes == 0 || !b.solvable() {
+            return None;
+        }
+        let mut search = SmaSearch {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            open: BTreeSet::new(),
+            live: 0,
+        };
+        search.add(SmaNode {
+            board: b.clone(),
+            spent: 0,
+            cost: Astar::manhattan(b),
+            parent: None,
+            dir: None,
+            pending: SmaStar::successors(b, None),
+            forgotten: Vec::new(),
+            children: Vec::new(),
+        });
+        loop {
+            let (cost, _, Reverse(id)) = *search.open.iter().next()?;
+            if cost == usize::MAX {
+                return None;
+            }
+            if search.node(id).board.solved() {
+                return Some(search.moves(id));
+            }
+            if search.live >= max_nodes && !search.drop_worst(id) {
+                return None;
+            }
+            let node = search.node_mut(id);
+            // Generate the successors in order, then the forgotten ones from the best
+            let (dir, floor) = match node.pending.pop() {
+                Some(dir) => (dir, 0),
+                None => {
+                    let best = (0..node.forgotten.len())
+                        .min_by_key(|&i| node.forgotten[i].1)
+                        .expect("nodes without successors to generate are not open");
+                    node.forgotten.swap_remove(best)
+                }
+            };
+            let mut board = node.board.clone();
+            board.slide(dir);
+            let spent = node.spent + 1;
+            // Without room for its successors, a board that is not solved is a dead end
+            let cost = if !board.solved() && spent + 1 >= max_nodes {
+                usize::MAX
+            } else {
+                (spent + Astar::manhattan(&board)).max(node.cost).max(floor)
+            };
+            let pending = SmaStar::successors(&board, Some(dir));
+            let child = search.add(SmaNode {
+                board,
+                spent,
+                cost,
+                parent: Some(id),
+                dir: Some(dir),
+                pending,
+                forgotten: Vec::new(),
+                children: Vec::new(),
+            });
+            let node = search.node_mut(id);
+            node.children.push(child);
+            if node.pending.is_empty() && node.forgotten.is_empty() {
+                let key = search.key(id);
+                search.open.remove(&key);
+            }
+            search.backup(id);
+        }
+    }
+
+    // Moves from `b` except the one undoing `last`, in reverse order of `Direction::all()`
+    fn successors(b: &Board, last: Option<Direction>) -> Vec<Direction> {
+        let mut dirs: Vec<Direction> = Direction::all()
+            .iter()
+            .cloned()
+            .filter(|&dir| b.can_slide(dir) && last.is_none_or(|l| !l.opposites(dir)))
+            .collect();
+        dirs.reverse();
+        dirs
+    }
+}
+
+// Solutions of previously solved boards, shared between a board and its mirror image (see
+// `Board::transposed`) by storing them under their canonical form. Rotated boards are separate
+// puzzles and get their own entries, see `Board::canonical`.
+#[derive(Debug, Default, Clone)]
+pub struct SymmetryCache {
+    solutions: BTreeMap<Board, Option<Vec<Direction>>>,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl SymmetryCache {
+    pub fn new() -> SymmetryCache {
+        Default::default()
+    }
+
+    // Optimal solution of `b`, solving its canonical form only if neither `b` nor its mirror
+    // image were solved before
+    pub fn solve(&mut self, b: &Board) -> Option<Vec<Direction>> {
+        let canonical = b.canonical();
+        let moves = match self.solutions.get(&canonical) {
+            Some(moves) => {
+                self.hits += 1;
+                moves.clone()
+            }
+            None => {
+                self.misses += 1;
+                let moves = Astar::solve(&canonical).ok();
+                self.solutions.insert(canonical.clone(), moves.clone());
+                moves
+            }
+        };
+        if *b == canonical {
+            moves
+        } else {
+            moves.map(|moves| moves.iter().map(|dir| dir.transposed()).collect())
+        }
+    }
+}
+
+// Largest optimal number of moves over every solvable `size`x`size` board, found by flooding
+// the whole state space breadth-first from the solved board. Only feasible up to 3x3 (the
+// 8-puzzle has 181440 reachable states; the 15-puzzle has over 10 trillion).
+pub fn gods_number(size: usize) -> Result<usize, &'static str> {
+    match size {
+        0 => Err("size should be at least 1"),
+        1 => Ok(0),
+        2 => Ok(flood_depth::<2>()),
+        3 => Ok(flood_depth::<3>()),
+        _ => Err("state space is too large to be explored"),
+    }
+}
+
+// Number of breadth-first layers needed to reach every board from the solved `N`x`N` board
+fn flood_depth<const N: usize>() -> usize {
+    let goal = Board::<N>::goal();
+    let mut seen = HashSet::new();
+    seen.insert(goal.clone());
+    let mut frontier = vec![goal];
+    let mut depth = 0;
+    loop {
+        let mut next = Vec::new();
+        for b in frontier {
+            let empty = b.empty_index();
+            for pos in Board::<N>::neighbours(empty) {
+                let mut tiles = b.tiles().to_vec();
+                tiles.swap(empty, pos);
+                let moved = Board::<N>::new_from(&tiles).expect("a slide keeps the tiles valid");
+                if seen.insert(moved.clone()) {
+                    next.push(moved);
+                }
+            }
+        }
+        if next.is_empty() {
+            return depth;
+        }
+        frontier = next;
+        depth += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use rand::thread_rng;
+
+    const SOLVABLE_CONFIG: &[u8; 16] = &[1, 6, 2, 9, 7, 8, 4, 0, 13, 5, 3, 11, 15, 14, 10, 12];
+    const ALMOST_CONFIG: &[u8; 16] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+    const EASY_CONFIG: &[u8; 16] = &[1, 2, 3, 4, 5, 0, 6, 8, 9, 10, 7, 11, 13, 14, 15, 12];
+    const SOLVED_CONFIG: &[u8; 16] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+    // Has two optimal solutions
+    const TWO_WAYS_CONFIG: &[u8; 16] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 14, 12, 13, 11, 10, 15];
+
+    // Board reached by `steps` random moves from the solved board
+    fn scrambled(steps: usize) -> Board {
+        let mut rng = thread_rng();
+        let mut board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        let mut last: Option<Direction> = None;
+        for _ in 0..steps {
+            let dirs: Vec<Direction> = Direction::all()
+                .iter()
+                .cloned()
+                .filter(|&d| board.can_slide(d) && last.is_none_or(|l| !l.opposites(d)))
+                .collect();
+            let dir = *dirs.choose(&mut rng).expect("no legal moves?!");
+            board.slide(dir);
+            last = Some(dir);
+        }
+        board
+    }
+
+    // Check that `heuristic` never overestimates the optimal number of moves of `samples`
+    fn assert_admissible<H: Fn(&Board) -> usize>(heuristic: H, samples: &[Board]) {
+        for b in samples {
+            let optimal = Astar::run(b).expect("sample should be solvable").len();
+            let estimate = heuristic(b);
+            assert!(
+                estimate <= optimal,
+                "heuristic {} exceeds optimal {} for\n{}",
+                estimate,
+                optimal,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn heuristics_admissible() {
+        let mut samples: Vec<Board> = (0..20).map(|i| scrambled(4 + i % 12)).collect();
+        samples.push(Board::new_from(ALMOST_CONFIG).expect("failed to create almost board"));
+        samples.push(Board::new_from(SOLVED_CONFIG).expect("failed to create solved board"));
+        assert_admissible(Astar::manhattan, &samples);
+        assert_admissible(Astar::linear_conflict, &samples);
+    }
+
+    #[test]
+    fn linear_conflict() {
+        let solved = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(Astar::linear_conflict(&solved), 0);
+        // 1 and 2 swapped in their goal row
+        let tiles = [2, 1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+        let b = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(Astar::manhattan(&b), 2);
+        assert_eq!(Astar::linear_conflict(&b), 4);
+    }
+
+    #[test]
+    fn solve_almost_solved() {
+        let res = Board::new_from(ALMOST_CONFIG);
+        assert!(res.is_ok());
+        let mut board: Board = res.expect("failed to create almost board");
+        assert!(board.solvable());
+        match Astar::run(&board) {
+            Some(moves) => {
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
+            }
+            None => panic!("result should not be None"),
+        }
+    }
+
+    #[test]
+    fn solve_solvable() {
+        let res = Board::new_from(SOLVABLE_CONFIG);
+        assert!(res.is_ok());
+        let mut board: Board = res.expect("failed to create solvable board");
+        assert!(board.solvable());
+        match Astar::run(&board) {
+            Some(moves) => {
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
+            }
+            None => panic!("result should not be None"),
+        }
+    }
+
+    #[test]
+    fn solve_reverse() {
+        let res = Board::new_from(EASY_CONFIG);
+        assert!(res.is_ok());
+        let start = res.expect("failed to create easy board");
+        match Astar::run_reverse(&start) {
+            Some(moves) => {
+                let mut board =
+                    Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert_eq!(board, start);
+            }
+            None => panic!("result should not be None"),
+        }
     }
-}
 
-impl PartialOrd for Solution {
-    fn partial_cmp(&self, other: &Solution) -> Option<Ordering> {
-        Some(self.cmp(other))
+    #[test]
+    fn solve_with_priority() {
+        let res = Board::new_from(SOLVABLE_CONFIG);
+        assert!(res.is_ok());
+        let mut board = res.expect("failed to create solvable board");
+        let priority = |b: &Board, moves: usize| moves + Astar::linear_conflict(b);
+        match Astar::run_with_priority(&board, priority) {
+            Some(moves) => {
+                assert_eq!(Some(moves.len()), Astar::run(&board).map(|m| m.len()));
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
+            }
+            None => panic!("result should not be None"),
+        }
+    }
+
+    #[test]
+    fn solve_weighted_tiles() {
+        let res = Board::new_from(EASY_CONFIG);
+        assert!(res.is_ok());
+        let mut board = res.expect("failed to create easy board");
+        let weights = [1; 16];
+        match Astar::run_weighted_tiles(&board, &weights) {
+            Some(moves) => {
+                assert_eq!(Some(moves.clone()), Astar::run(&board));
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
+            }
+            None => panic!("result should not be None"),
+        }
+        assert_eq!(Astar::run_weighted_tiles(&board, &weights[..15]), None);
     }
-}
 
-static DIRECTIONS: [Direction; 4] = [
-    Direction::Left,
-    Direction::Right,
-    Direction::Up,
-    Direction::Down,
-];
+    #[test]
+    fn solve_mem_limited() {
+        let res = Board::new_from(EASY_CONFIG);
+        assert!(res.is_ok());
+        let mut board = res.expect("failed to create easy board");
+        match Astar::run_mem_limited(&board, 1 << 20) {
+            Ok(moves) => {
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+        }
 
-pub struct Astar;
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert_eq!(
+            Astar::run_mem_limited(&board, 1024),
+            Err(SolveError::MemoryLimit)
+        );
 
-impl Astar {
-    // Calculate the Manhattan distance of a value `val` at index `idx`
-    fn manhattan_dist(tile: u8, idx: usize) -> usize {
-        let val = tile as isize;
-        // Linear distance from where tile should be
-        let diff = match val.checked_sub(1 + idx as isize) {
-            Some(v) => v.wrapping_abs() as u32 as usize,
-            None => panic!("Manhattan distance should not overflow: {}-1-{}", val, idx),
-        };
-        ((diff / 4) + (diff % 4)) // # of rows + cols to move
+        // The closed set counts too: a limit the frontier alone never exceeds is still too small
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let mut search = Search::new(&board, Astar::manhattan, Options::default(), Solution::cmp);
+        let mut peak = search.used;
+        while let Ok(Step::Expanded(_)) = search.step() {
+            peak = peak.max(search.used);
+        }
+        assert!(search.used_bytes() > search.used);
+        assert_eq!(
+            Astar::run_mem_limited(&board, peak),
+            Err(SolveError::MemoryLimit)
+        );
+        for &backend in [Backend::Heap, Backend::Buckets].iter() {
+            assert_eq!(
+                Astar::run_backend_mem_limited(&board, backend, 1024),
+                Err(SolveError::MemoryLimit)
+            );
+            assert_eq!(
+                Astar::run_backend_mem_limited(&board, backend, 1 << 20),
+                Ok(Astar::run(&board).unwrap())
+            );
+        }
     }
 
-    pub fn run(b: &Board) -> Option<Vec<Direction>> {
-        let mut heap = BinaryHeap::new();
-        let cost: usize = b
-            .tiles()
-            .iter()
-            .enumerate()
-            .map(|(i, t)| Astar::manhattan_dist(*t, i))
-            .sum();
-        heap.push(Solution {
-            cost,
-            moves: vec![],
-            board: b.clone(),
-        });
-
-        while let Some(Solution {
-            ref moves,
-            ref board,
-            ..
-        }) = heap.pop()
-        {
-            if board.solved() {
-                return Some(moves.to_vec());
+    #[test]
+    fn solve_avoiding() {
+        let res = Board::new_from(EASY_CONFIG);
+        assert!(res.is_ok());
+        let mut board = res.expect("failed to create easy board");
+        let optimal = Astar::run(&board).expect("result should not be None");
+        match Astar::run_avoiding(&board, &[0]) {
+            Some(moves) => {
+                assert_eq!(moves.len(), optimal.len());
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
             }
-            let n_moves = moves.len();
-            for &dir in DIRECTIONS.iter() {
-                // Do not undo last move
-                if let Some(last) = moves.last() {
-                    if last.opposites(dir) {
-                        continue;
-                    }
+            None => panic!("result should not be None"),
+        }
+
+        // The empty tile is walled off from its goal position
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(Astar::run_avoiding(&board, &[11, 14]), None);
+        assert_eq!(Astar::run_avoiding(&board, &[15]), None);
+
+        // Tiles 6 and 7 are swapped on forbidden positions, so they can never be put back
+        let tiles = [2, 1, 3, 4, 5, 7, 6, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+        let board = Board::new_from(&tiles).expect("failed to create board");
+        assert!(board.solvable());
+        assert_eq!(Astar::run_avoiding(&board, &[5, 6]), None);
+
+        // Only the outer ring is left, where tiles can rotate but never change their order
+        let tiles = [2, 1, 4, 3, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0];
+        let board = Board::new_from(&tiles).expect("failed to create board");
+        assert!(board.solvable());
+        assert_eq!(Astar::run_avoiding(&board, &[5, 6, 9, 10]), None);
+        let ring = Astar::blank_region(15, &[5, 6, 9, 10]);
+        assert!(!Astar::wilson_region(&ring));
+        assert!(Astar::wilson_region(&Astar::blank_region(15, &[0])));
+    }
+
+    #[test]
+    fn solve_with_comparator() {
+        let res = Board::new_from(EASY_CONFIG);
+        assert!(res.is_ok());
+        let mut board = res.expect("failed to create easy board");
+        // Breadth-first: ignore the heuristic and expand the shallowest nodes first
+        let bfs = |a: &Solution, b: &Solution| b.moves().len().cmp(&a.moves().len());
+        match Astar::run_with_comparator(&board, bfs) {
+            Some(moves) => {
+                assert_eq!(Some(moves.len()), Astar::run(&board).map(|m| m.len()));
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
                 }
-                if !board.can_slide(dir) {
-                    continue;
+                assert!(board.solved());
+            }
+            None => panic!("result should not be None"),
+        }
+    }
+
+    #[test]
+    fn solve_partial() {
+        let res = Board::new_from(SOLVABLE_CONFIG);
+        assert!(res.is_ok());
+        let mut board = res.expect("failed to create solvable board");
+        match Astar::run_partial(&board, &[1, 2, 3, 4]) {
+            Some(moves) => {
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
                 }
-                let mut b = board.clone();
-                b.slide(dir);
-                let mut nc: usize = b
-                    .tiles()
-                    .iter()
-                    .enumerate()
-                    .map(|(i, t)| Astar::manhattan_dist(*t, i))
-                    .sum();
-                nc += n_moves + 1;
-                let mut nm = moves.clone();
-                nm.push(dir);
-                heap.push(Solution {
-                    cost: nc,
-                    moves: nm,
-                    board: b,
-                });
+                assert_eq!(board.tiles()[..4], [1, 2, 3, 4]);
+                assert!(!board.solved());
             }
+            None => panic!("result should not be None"),
         }
-        None
+
+        // An empty pattern is already in place
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create solvable board");
+        assert_eq!(Astar::run_partial(&board, &[]), Some(vec![]));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn solve_to_custom_goal() {
+        // Empty tile first, then the tiles in order
+        let goal = Board::new();
+        let mut board = goal.clone();
+        for &dir in [
+            Direction::Right,
+            Direction::Down,
+            Direction::Right,
+            Direction::Down,
+        ]
+        .iter()
+        {
+            board.slide(dir);
+        }
+        let moves = Astar::run_to(&board, &goal).expect("no solution found");
+        assert_eq!(moves.len(), 4);
+        for dir in moves {
+            assert!(board.slide_safe(dir).is_ok());
+        }
+        assert!(board.solved_to(&goal));
+        assert!(!board.solved());
 
-    const SOLVABLE_CONFIG: &'static [u8; 16] =
-        &[1, 6, 2, 9, 7, 8, 4, 0, 13, 5, 3, 11, 15, 14, 10, 12];
-    const ALMOST_CONFIG: &'static [u8; 16] =
-        &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 15];
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(Astar::run_to(&board, &Board::goal()), Astar::run(&board));
+        assert_eq!(Astar::run_to(&board, &goal), None);
+    }
 
     #[test]
-    fn solve_almost_solved() {
+    fn solve_stable() {
+        let samples = vec![
+            Board::new_from(SOLVABLE_CONFIG).expect("failed to create board"),
+            Board::new_from(TWO_WAYS_CONFIG).expect("failed to create board"),
+            scrambled(20),
+        ];
+        for board in samples {
+            let moves = Astar::run_stable(&board).expect("no solution found");
+            assert!(moves.len() >= Astar::run(&board).unwrap().len());
+            let mut b = board.clone();
+            for dir in moves {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert!(b.solved());
+        }
+    }
+
+    #[test]
+    fn ida_star() {
+        let board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(IDAStar::run(&board), Some(vec![]));
+        assert_eq!(IDAStar::run(&Board::new()), None);
+
+        let mut boards = vec![Board::new_from(EASY_CONFIG).expect("failed to create board")];
+        boards.extend((0..10).map(|i| scrambled(10 + 3 * i)));
+        for board in boards {
+            let moves = IDAStar::run(&board).expect("no solution found");
+            assert_eq!(moves.len(), Astar::run(&board).unwrap().len());
+            assert!(moves.windows(2).all(|win| !win[0].opposites(win[1])));
+            let mut b = board.clone();
+            for dir in moves {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert!(b.solved());
+        }
+    }
+
+    #[test]
+    fn ida_star_thresholds() {
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let mut thresholds = Vec::new();
+        let moves = IDAStar::run_with_threshold_callback(&board, |t| thresholds.push(t))
+            .expect("no solution found");
+        assert_eq!(moves.len(), Astar::run(&board).unwrap().len());
+        assert!(thresholds.windows(2).all(|win| win[0] < win[1]));
+        assert_eq!(thresholds.last(), Some(&moves.len()));
+        assert_eq!(thresholds.first(), Some(&Astar::manhattan(&board)));
+
+        let mut b = board.clone();
+        for dir in moves {
+            assert!(b.slide_safe(dir).is_ok());
+        }
+        assert!(b.solved());
+    }
+
+    #[test]
+    fn sma_star() {
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let optimal = Astar::run(&board).expect("no solution found");
+        assert_eq!(
+            SmaStar::run(&board, 100_000).map(|m| m.len()),
+            Some(optimal.len())
+        );
+
+        // 16 moves away from the solved board
+        let tiles = [1, 2, 3, 4, 13, 0, 10, 8, 6, 5, 7, 12, 9, 14, 11, 15];
+        let board = Board::new_from(&tiles).expect("failed to create board");
+        for &max_nodes in [17, 20, 30].iter() {
+            let moves = SmaStar::run(&board, max_nodes).expect("no solution found");
+            let mut b = board.clone();
+            for dir in moves {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert!(b.solved());
+        }
+        // The path itself does not fit in memory
+        assert_eq!(SmaStar::run(&board, 10), None);
+
+        let board = Board::new_from(TWO_WAYS_CONFIG).expect("failed to create board");
+        assert_eq!(SmaStar::run(&board, 8).map(|m| m.len()), Some(7));
+    }
+
+    #[test]
+    fn symmetry_cache() {
+        let mut cache = SymmetryCache::new();
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let mirror = board.transposed();
+        assert_ne!(board, mirror);
+
+        let moves = cache.solve(&board).expect("no solution found");
+        assert_eq!(moves.len(), Astar::run(&board).unwrap().len());
+        assert_eq!((cache.hits, cache.misses), (0, 1));
+        assert_eq!(cache.solve(&board), Some(moves.clone()));
+        let mirrored = cache.solve(&mirror).expect("no solution found");
+        assert_eq!((cache.hits, cache.misses), (2, 1));
+
+        let mut b = mirror;
+        for dir in mirrored {
+            assert!(b.slide_safe(dir).is_ok());
+        }
+        assert!(b.solved());
+    }
+
+    #[test]
+    fn solve_best_effort() {
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        for &max_nodes in [0, 1, 10, 100].iter() {
+            let (moves, reached) = Astar::best_effort_progress(&board, max_nodes);
+            assert!(Astar::manhattan(&reached) <= Astar::manhattan(&board));
+            let mut b = board.clone();
+            for dir in moves {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert_eq!(b, reached);
+        }
+        let (_, reached) = Astar::best_effort_progress(&board, 100);
+        assert!(Astar::manhattan(&reached) < Astar::manhattan(&board));
+
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let (moves, reached) = Astar::best_effort_progress(&board, 1000);
+        assert!(reached.solved());
+        assert_eq!(Some(moves), Astar::run(&board));
+    }
+
+    #[test]
+    fn solve_depth_profile() {
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let (moves, profile) = Astar::run_depth_profile(&board);
+        assert_eq!(moves, Astar::run(&board));
+        assert_eq!(profile[0], 1);
+        assert!(profile.len() <= moves.unwrap().len());
+        // Every popped board but the solved one is expanded
+        let expanded = Astar::search_iter(&board).count() - 1;
+        assert_eq!(profile.iter().sum::<usize>(), expanded);
+
+        let board = Board::new_from(SOLVED_CONFIG).expect("failed to create solved board");
+        assert_eq!(Astar::run_depth_profile(&board), (Some(vec![]), vec![]));
+    }
+
+    #[test]
+    fn solve_backends() {
+        let mut boards: Vec<Board> = (0..10).map(|i| scrambled(10 + 2 * i)).collect();
+        boards.push(Board::new_from(SOLVABLE_CONFIG).expect("failed to create board"));
+        for board in boards {
+            let heap = Astar::run_backend(&board, Backend::Heap).expect("no solution found");
+            let buckets = Astar::run_backend(&board, Backend::Buckets).expect("no solution found");
+            assert_eq!(heap.len(), buckets.len());
+            let mut b = board.clone();
+            for dir in buckets {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert!(b.solved());
+        }
+        assert_eq!(Backend::default(), Backend::Heap);
+    }
+
+    #[test]
+    #[ignore]
+    fn backends_benchmark() {
+        let boards: Vec<Board> = (0..20).map(|_| scrambled(40)).collect();
+        for &backend in [Backend::Heap, Backend::Buckets].iter() {
+            let start = std::time::Instant::now();
+            for board in boards.iter() {
+                Astar::run_backend(board, backend).expect("no solution found");
+            }
+            println!("{:?}: {:?}", backend, start.elapsed());
+        }
+    }
+
+    #[test]
+    fn solve_with_alternative() {
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(
+            Astar::run_with_alternative(&board),
+            Some((Astar::run(&board).unwrap(), None))
+        );
+
+        let board = Board::new_from(TWO_WAYS_CONFIG).expect("failed to create board");
+        let (best, alternative) = Astar::run_with_alternative(&board).expect("no solution found");
+        let alternative = alternative.expect("no alternative found");
+        assert_eq!(best.len(), alternative.len());
+        assert_ne!(best[0], alternative[0]);
+    }
+
+    #[test]
+    fn solve_all_optimal() {
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(
+            Astar::all_optimal(&board),
+            vec![Astar::run(&board).unwrap()]
+        );
+
+        let board = Board::new_from(TWO_WAYS_CONFIG).expect("failed to create board");
+        let all = Astar::all_optimal(&board);
+        assert_eq!(all.len(), 2);
+        assert_ne!(all[0], all[1]);
+        for moves in all.iter() {
+            assert_eq!(moves.len(), 7);
+            let mut b = board.clone();
+            for &dir in moves.iter() {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert!(b.solved());
+        }
+    }
+
+    #[test]
+    fn solve_other_sizes() {
+        let board =
+            Board::<3>::new_from(&[1, 2, 3, 4, 5, 6, 0, 7, 8]).expect("failed to create board");
+        assert_eq!(board.solve(), Ok(vec![Direction::Right, Direction::Right]));
+
+        let board =
+            Board::<3>::new_from(&[8, 1, 3, 4, 0, 2, 7, 6, 5]).expect("failed to create board");
+        let moves = board.solve().expect("no solution found");
+        assert_eq!(moves.len(), 14);
+        let mut b = board.clone();
+        for &dir in moves.iter() {
+            assert!(b.slide_safe(dir).is_ok());
+        }
+        assert!(b.solved());
+        let board =
+            Board::<3>::new_from(&[2, 1, 3, 4, 5, 6, 7, 8, 0]).expect("failed to create board");
+        assert_eq!(board.solve(), Err(SolveError::Unsolvable));
+
+        let mut board = Board::<5>::goal();
+        for &dir in [
+            Direction::Up,
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+        ]
+        .iter()
+        {
+            board.slide(dir);
+        }
+        let moves = board.solve().expect("no solution found");
+        assert_eq!(moves.len(), 4);
+    }
+
+    #[test]
+    fn expand_boards_once() {
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let mut seen = HashSet::new();
+        for event in Astar::search_iter(&board) {
+            assert!(seen.insert(event.board), "board expanded twice");
+        }
+        assert!(seen.iter().any(|b| b.solved()));
+    }
+
+    #[test]
+    fn solve_optimal_limited() {
+        let board = Board::new_from(TWO_WAYS_CONFIG).expect("failed to create board");
+        assert_eq!(Astar::optimal_solutions_limited(&board, 1).len(), 1);
+        assert_eq!(Astar::optimal_solutions_limited(&board, 5).len(), 2);
+        assert!(Astar::optimal_solutions_limited(&board, 0).is_empty());
+
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let optimal = Astar::run(&board).expect("no solution found").len();
+        let found = Astar::optimal_solutions_limited(&board, 3);
+        assert!(!found.is_empty() && found.len() <= 3);
+        for (i, moves) in found.iter().enumerate() {
+            assert_eq!(moves.len(), optimal);
+            assert!(!found[..i].contains(moves));
+        }
+    }
+
+    #[test]
+    fn solve_with_bound() {
+        let board = Board::new_from(SOLVABLE_CONFIG).expect("failed to create board");
+        let optimal = Astar::run(&board).expect("no solution found").len();
+        let moves = Astar::run_with_bound(&board, optimal).expect("no bounded solution found");
+        assert_eq!(moves.len(), optimal);
+        assert_eq!(Astar::run_with_bound(&board, optimal - 1), None);
+        assert_eq!(
+            Astar::run_with_bound(&board, optimal + 10).map(|m| m.len()),
+            Some(optimal)
+        );
+
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(Astar::run_with_bound(&board, 0), None);
+        let opts = Options {
+            max_cost: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            Astar::search(&board, Astar::manhattan, opts),
+            Err(SolveError::Exhausted)
+        );
+        let mut tiles = *SOLVED_CONFIG;
+        tiles.swap(0, 1);
+        let board = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(Astar::run_with_bound(&board, 10), None);
+    }
+
+    #[test]
+    fn solve_partial_preferring() {
+        let tiles = [1, 2, 3, 4, 5, 0, 7, 8, 9, 6, 10, 15, 13, 14, 12, 11];
+        let board = Board::new_from(&tiles).expect("failed to create board");
+        // Two optimal ways to place 10 and 11, leaving the empty tile at 11 or at 14
+        for &blank in &[11, 14] {
+            let moves = Astar::run_partial_preferring(&board, &[10, 11], blank)
+                .expect("result should not be None");
+            assert_eq!(moves.len(), 7);
+            let mut b = board.clone();
+            for &dir in moves.iter() {
+                assert!(b.slide_safe(dir).is_ok());
+            }
+            assert_eq!(b.tiles()[9..11], [10, 11]);
+            assert_eq!(b.empty_index(), blank);
+        }
+    }
+
+    #[test]
+    fn solve_gravity() {
         let res = Board::new_from(ALMOST_CONFIG);
         assert!(res.is_ok());
         let mut board = res.expect("failed to create almost board");
-        assert!(board.solvable());
-        match Astar::run(&board) {
+        match Astar::run_gravity(&board) {
+            Some(moves) => {
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
+            }
+            None => panic!("result should not be None"),
+        }
+
+        let mut board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert!(Gravity.heuristic(&board) <= 6);
+        match Astar::run_gravity(&board) {
             Some(moves) => {
+                let cost: usize = moves
+                    .iter()
+                    .map(|&dir| Gravity.step_cost(&board, dir))
+                    .sum();
+                // Right, Down, Right, Down
+                assert_eq!(cost, 6);
                 for &dir in moves.iter() {
-                    assert!(!board.slide_safe(dir).is_err());
+                    assert!(board.slide_safe(dir).is_ok());
                 }
                 assert!(board.solved());
             }
@@ -130,31 +2298,148 @@ mod tests {
     }
 
     #[test]
-    fn solve_solvable() {
+    fn solve_with_capacity() {
         let res = Board::new_from(SOLVABLE_CONFIG);
         assert!(res.is_ok());
-        let mut board = res.expect("failed to create solvable board");
-        assert!(board.solvable());
-        match Astar::run(&board) {
+        let mut board: Board = res.expect("failed to create solvable board");
+        match Astar::run_with_capacity(&board, 1 << 12) {
+            Some(moves) => {
+                assert_eq!(Some(moves.len()), Astar::run(&board).map(|m| m.len()));
+                for &dir in moves.iter() {
+                    assert!(board.slide_safe(dir).is_ok());
+                }
+                assert!(board.solved());
+            }
+            None => panic!("result should not be None"),
+        }
+    }
+
+    #[test]
+    fn search_events() {
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let events: Vec<SearchEvent> = Astar::search_iter(&board).collect();
+        assert_eq!(events[0].board, board);
+        assert_eq!(events[0].depth, 0);
+        let last = events.last().expect("there should be events");
+        assert!(last.board.solved());
+        assert_eq!(last.depth, 4);
+        assert_eq!(last.cost, 4);
+        assert_eq!(events.iter().filter(|e| e.board.solved()).count(), 1);
+    }
+
+    #[test]
+    fn solve_dfs() {
+        let res = Board::new_from(ALMOST_CONFIG);
+        assert!(res.is_ok());
+        let mut board = res.expect("failed to create almost board");
+        match Dfs::run(&board, 5) {
             Some(moves) => {
+                assert!(moves.len() <= 5);
                 for &dir in moves.iter() {
-                    assert!(!board.slide_safe(dir).is_err());
+                    assert!(board.slide_safe(dir).is_ok());
                 }
                 assert!(board.solved());
             }
             None => panic!("result should not be None"),
         }
+
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(Dfs::run(&board, 3), None);
+        assert!(Dfs::run(&board, 6).is_some());
+    }
+
+    #[test]
+    fn step_solver() {
+        let board = Board::new_from(ALMOST_CONFIG).expect("failed to create almost board");
+        let mut solver = StepSolver::new(&board);
+        let mut steps = 0;
+        let moves = loop {
+            match solver.step() {
+                StepResult::Progress(_) => steps += 1,
+                StepResult::Solved(moves) => break moves,
+                StepResult::Exhausted => panic!("search should not be exhausted"),
+            }
+            assert!(steps < 1000, "too many steps");
+        };
+        assert_eq!(moves, vec![Direction::Right]);
+        assert_eq!(solver.step(), StepResult::Solved(moves));
+
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let mut solver = StepSolver::new(&board);
+        assert_eq!(solver.step(), StepResult::Progress(4));
+    }
+
+    #[test]
+    fn gods_number_small() {
+        assert_eq!(gods_number(1), Ok(0));
+        assert_eq!(gods_number(2), Ok(6));
+        assert!(gods_number(0).is_err());
+        assert!(gods_number(4).is_err());
     }
 
     #[test]
     #[ignore]
+    fn gods_number_8_puzzle() {
+        assert_eq!(gods_number(3), Ok(31));
+    }
+
+    #[test]
+    fn solve_cancellable() {
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let cancel = AtomicBool::new(false);
+        assert_eq!(
+            Astar::run_cancellable(&board, &cancel).ok(),
+            Astar::run(&board)
+        );
+
+        cancel.store(true, AtomicOrdering::Relaxed);
+        assert_eq!(
+            Astar::run_cancellable(&board, &cancel),
+            Err(SolveError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn solve_with_timeout() {
+        let board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        let timeout = Duration::from_secs(10);
+        assert_eq!(
+            Astar::run_with_timeout(&board, timeout).ok(),
+            Astar::run(&board)
+        );
+
+        let board = Board::new_seeded(1);
+        let timeout = Duration::from_millis(10);
+        assert_eq!(
+            Astar::run_with_timeout(&board, timeout),
+            Err(SolveError::Timeout)
+        );
+    }
+
+    #[test]
+    fn solve_checks_solvable() {
+        let board: Board = Board::new_from(EASY_CONFIG).expect("failed to create easy board");
+        assert_eq!(Astar::solve(&board).ok(), Astar::run(&board));
+
+        // Swapping two tiles makes the board unsolvable; the search would never end
+        let mut tiles = *EASY_CONFIG;
+        tiles.swap(0, 1);
+        let board: Board = Board::new_from(&tiles).expect("failed to create board");
+        assert_eq!(Astar::solve(&board), Err(SolveError::Unsolvable));
+        let board: Board = Board::new();
+        assert_eq!(Astar::solve(&board), Err(SolveError::Unsolvable));
+    }
+
+    // `Board::new_random` boards are about 50 moves away from the solved board and can take a
+    // long time to solve, so this scrambles a fixed number of moves instead
+    #[test]
     fn solve_random() {
-        let mut board = Board::new_random();
+        let mut board = scrambled(30);
         assert!(board.solvable());
         match Astar::run(&board) {
             Some(moves) => {
                 for dir in moves {
-                    assert!(!board.slide_safe(dir).is_err());
+                    assert!(board.slide_safe(dir).is_ok());
                 }
                 assert!(board.solved());
             }
@@ -0,0 +1,53 @@
+use crate::board::*;
+
+impl Board {
+    // Render the board as an SVG document of `cell_px` wide square tiles, leaving the empty tile
+    // blank
+    pub fn to_svg(&self, cell_px: u32) -> String {
+        let side = SIZE as u32 * cell_px;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" \
+             viewBox=\"0 0 {0} {0}\">\n",
+            side
+        );
+        for (i, &t) in self.tiles().iter().enumerate() {
+            if t == 0 {
+                continue;
+            }
+            let x = (i % SIZE) as u32 * cell_px;
+            let y = (i / SIZE) as u32 * cell_px;
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" fill=\"burlywood\" \
+                 stroke=\"black\"/>\n",
+                x, y, cell_px
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" \
+                 dominant-baseline=\"central\">{}</text>\n",
+                x + cell_px / 2,
+                y + cell_px / 2,
+                cell_px / 2,
+                t
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_elements() {
+        let b = Board::new();
+        let svg = b.to_svg(40);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("width=\"160\""));
+        assert_eq!(svg.matches("<rect ").count(), SIZE * SIZE - 1);
+        assert_eq!(svg.matches("<text ").count(), SIZE * SIZE - 1);
+        assert!(svg.contains(">15</text>"));
+        assert!(!svg.contains(">0</text>"));
+    }
+}